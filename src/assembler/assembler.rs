@@ -0,0 +1,587 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{
+        Path,
+        PathBuf
+    }
+};
+
+use crate::{
+    disassembler::header::NesHeader,
+    models::nesutil_model::{
+        NesUtil,
+        Util,
+        Save
+    },
+    utils::{
+        addressing::AddressingMode,
+        error::NesError,
+        opcode::{
+            find_opcode,
+            OpCode
+        },
+        util::{
+            create_and_write_file,
+            path_to_name
+        }
+    }
+};
+
+use super::{
+    expression::{
+        evaluate,
+        ExprError
+    },
+    parser::{
+        scoped,
+        Index,
+        InesField,
+        Operand,
+        Parser,
+        SourceLine,
+        Statement
+    }
+};
+
+/// Byte written by `.org` up to the address
+const FILL_VALUE: u8 = 0x00;
+
+/// Two-pass 6502 assembler for the asm6 syntax written by the disassembler,
+/// with the NESASM `.ines*` header directives.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::assembler::assembler::Assembler;
+///
+/// let source = "
+/// PPUCTRL equ $2000
+///
+/// .base $c000
+/// reset:
+///     lda #$80
+///     sta PPUCTRL
+/// loop:
+///     bne loop
+///     hex ff
+/// ";
+///
+/// let bytes = Assembler::new().assemble(source).unwrap();
+///
+/// assert_eq!(bytes, [0xa9, 0x80, 0x8d, 0x00, 0x20, 0xd0, 0xfe, 0xff]);
+/// ```
+///
+/// Macros, local labels, expressions and an iNES header:
+///
+/// ```
+/// use nes_utils::assembler::assembler::Assembler;
+///
+/// let source = r#"
+///     .inesprg 1
+///     .inesmap 2
+///     .inesmir 1
+///
+/// .macro store value, address
+///     lda #value
+///     sta address
+/// .endm
+///
+/// .org $c000
+/// reset:
+///     store <(table + 1), $00
+/// @wait:
+///     bit $2002
+///     bpl @wait
+///     jmp reset
+/// table:
+///     .db "OK", 2 * 3
+///     .dw table, reset
+/// "#;
+///
+/// let mut assembler = Assembler::new();
+/// let bytes = assembler.assemble(source).unwrap();
+///
+/// assert_eq!(&bytes[..8], [0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00, 0x21, 0x00]);
+/// assert_eq!(&bytes[16..20], [0xa9, 0x0d, 0x85, 0x00]);
+/// assert_eq!(&bytes[28..], [0x4f, 0x4b, 0x06, 0x0c, 0xc0, 0x00, 0xc0]);
+/// assert_eq!(assembler.symbols()["reset@wait"], 0xc004);
+/// ```
+///
+/// The arithmetic of the expressions wraps around:
+///
+/// ```
+/// use nes_utils::assembler::assembler::Assembler;
+///
+/// let source = "
+/// NEGATED equ -(1 << 63)
+/// QUOTIENT equ (1 << 63) / -1
+/// REMAINDER equ (1 << 63) % -1
+/// ";
+///
+/// let mut assembler = Assembler::new();
+/// assembler.assemble(source).unwrap();
+///
+/// assert_eq!(assembler.symbols()["NEGATED"], i64::MIN);
+/// assert_eq!(assembler.symbols()["QUOTIENT"], i64::MIN);
+/// assert_eq!(assembler.symbols()["REMAINDER"], 0);
+/// ```
+#[derive(Debug, Default)]
+pub struct Assembler {
+    /// Files included from memory instead of the file system
+    files: HashMap<String, Vec<u8>>,
+    /// Directory of the relative `.incbin` and `.include` paths
+    directory: PathBuf,
+    symbols: HashMap<String, i64>,
+    header: HashMap<InesField, i64>
+}
+
+impl Assembler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make `data` available to `.incbin` and `.include` as `path`
+    pub fn add_file(&mut self, path: &str, data: &[u8]) -> &mut Self {
+        self.files.insert(path.to_string(), data.to_vec());
+        self
+    }
+
+    /// Symbols (labels and constants) of the last assembly,
+    /// the local labels are prefixed with their global label
+    pub fn symbols(&self) -> &HashMap<String, i64> {
+        &self.symbols
+    }
+
+    /// Symbols sorted by value, one `name = $xxxx` per line
+    pub fn symbol_map(&self) -> String {
+        let mut symbols: Vec<(&String, &i64)> = self.symbols.iter().collect();
+
+        symbols.sort_by_key(|(name, value)| (**value, *name));
+
+        symbols
+            .iter()
+            .map(|(name, value)| format!("{} = ${:04x}\n", name, value))
+            .collect()
+    }
+
+    /// Assemble a source file, relative paths are resolved from its directory
+    pub fn assemble_file(&mut self, path: &str) -> Result<Vec<u8>, NesError> {
+        let source = fs::read_to_string(path)
+            .map_err(|source| NesError::FileInvalid {
+                path: path.to_string(),
+                source
+            })?;
+
+        self.directory = Path::new(path)
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        self.assemble_source(path, &source)
+    }
+
+    /// Assemble source code, the output starts with an iNES header
+    /// if there are `.ines*` directives
+    pub fn assemble(&mut self, source: &str) -> Result<Vec<u8>, NesError> {
+        self.assemble_source("<source>", source)
+    }
+
+    fn assemble_source(&mut self, path: &str, source: &str) -> Result<Vec<u8>, NesError> {
+        let load = |file: &str| {
+            self.read(file)
+                .and_then(|data| String::from_utf8(data).map_err(|_| format!("{} is not text", file)))
+        };
+        let lines = Parser::new(&load).parse(path, source)?;
+
+        self.symbols = HashMap::new();
+        self.header = HashMap::new();
+
+        let opcodes = self.first_pass(&lines)?;
+        let mut ret = self.ines_header();
+
+        ret.extend(self.second_pass(&lines, &opcodes)?);
+
+        Ok(ret)
+    }
+
+    /// Define the symbols and choose the opcodes
+    fn first_pass(&mut self, lines: &[SourceLine]) -> Result<Vec<Option<OpCode>>, NesError> {
+        let mut opcodes = Vec::new();
+        // Constants using symbols defined later
+        let mut pending = Vec::new();
+        let mut pc: i64 = 0;
+        let mut size: i64 = 0;
+
+        for line in lines {
+            let start = pc;
+            let mut opcode = None;
+
+            match &line.statement {
+                Statement::Label(name) => self.define(line, name, pc)?,
+                Statement::Constant(name, expr) => {
+                    match self.try_evaluate(line, expr, pc)? {
+                        Some(value) => self.define(line, name, value)?,
+                        None => pending.push((line, pc))
+                    };
+                },
+                Statement::Org(expr) => {
+                    let address = self.evaluate(line, expr, pc)?;
+
+                    if size > 0 {
+                        size += self.padding(line, address, pc)?;
+                    }
+                    pc = address;
+                },
+                Statement::Base(expr) => pc = self.evaluate(line, expr, pc)?,
+                Statement::Hex(bytes) => pc += bytes.len() as i64,
+                Statement::Bytes(exprs) => pc += exprs.len() as i64,
+                Statement::Words(exprs) => pc += exprs.len() as i64 * 2,
+                Statement::Incbin(file) => pc += self.incbin(line, file)?.len() as i64,
+                Statement::Ines(field, expr) => {
+                    let value = self.evaluate(line, expr, pc)?;
+
+                    self.header.insert(*field, value);
+                },
+                Statement::Instruction(mnemonic, operand) => {
+                    let code = self.choose(line, mnemonic, operand, pc)?;
+
+                    pc += code.len as i64;
+                    opcode = Some(code);
+                }
+            };
+
+            if !matches!(line.statement, Statement::Org(_) | Statement::Base(_)) {
+                size += pc - start;
+            }
+
+            opcodes.push(opcode);
+        }
+
+        // Defined in the order they can be evaluated
+        while !pending.is_empty() {
+            let count = pending.len();
+
+            for (line, pc) in std::mem::take(&mut pending) {
+                let Statement::Constant(name, expr) = &line.statement else {
+                    continue;
+                };
+
+                match self.try_evaluate(line, expr, pc)? {
+                    Some(value) => self.define(line, name, value)?,
+                    None => pending.push((line, pc))
+                };
+            }
+
+            if pending.len() == count {
+                let (line, pc) = pending[0];
+                let Statement::Constant(_, expr) = &line.statement else {
+                    break;
+                };
+
+                self.evaluate(line, expr, pc)?;
+            }
+        }
+
+        Ok(opcodes)
+    }
+
+    /// Write the bytes, every symbol is known
+    fn second_pass(
+        &self,
+        lines: &[SourceLine],
+        opcodes: &[Option<OpCode>]
+    ) -> Result<Vec<u8>, NesError> {
+        let mut ret = Vec::new();
+        let mut pc: i64 = 0;
+
+        for (line, opcode) in lines.iter().zip(opcodes) {
+            let start = ret.len();
+
+            match &line.statement {
+                Statement::Label(_) | Statement::Constant(_, _) | Statement::Ines(_, _) => {},
+                Statement::Org(expr) => {
+                    let address = self.evaluate(line, expr, pc)?;
+
+                    if !ret.is_empty() {
+                        let padding = self.padding(line, address, pc)?;
+
+                        ret.resize(ret.len() + padding as usize, FILL_VALUE);
+                    }
+                    pc = address;
+                    continue;
+                },
+                Statement::Base(expr) => {
+                    pc = self.evaluate(line, expr, pc)?;
+                    continue;
+                },
+                Statement::Hex(bytes) => ret.extend(bytes),
+                Statement::Bytes(exprs) => {
+                    for expr in exprs {
+                        ret.push(self.byte(line, expr, pc)?);
+                    }
+                },
+                Statement::Words(exprs) => {
+                    for expr in exprs {
+                        ret.extend(self.word(line, expr, pc)?.to_le_bytes());
+                    }
+                },
+                Statement::Incbin(file) => ret.extend(self.incbin(line, file)?),
+                Statement::Instruction(_, operand) => {
+                    if let Some(opcode) = opcode {
+                        ret.push(opcode.code);
+                        ret.extend(self.operand_bytes(line, opcode, operand, pc)?);
+                    }
+                }
+            };
+
+            pc += (ret.len() - start) as i64;
+        }
+
+        Ok(ret)
+    }
+
+    /// 16 bytes iNES header, empty without `.ines*` directives
+    fn ines_header(&self) -> Vec<u8> {
+        if self.header.is_empty() {
+            return Vec::new();
+        }
+
+        let field = |field| *self.header.get(&field).unwrap_or(&0) as u8;
+        let mapper = field(InesField::Mapper);
+        let mut ret = vec![
+            0x4e, 0x45, 0x53, 0x1a,
+            field(InesField::Prg),
+            field(InesField::Chr),
+            (mapper & 0x0f) << 4 | (field(InesField::Mirroring) & 0x0f),
+            mapper & 0xf0
+        ];
+
+        ret.resize(NesHeader::HEADER_SIZE, 0x00);
+        ret
+    }
+
+    fn define(&mut self, line: &SourceLine, name: &str, value: i64) -> Result<(), NesError> {
+        if self.symbols.insert(name.to_string(), value).is_some() {
+            return Err(line.error(&format!("symbol {} defined twice", name)));
+        }
+
+        Ok(())
+    }
+
+    /// Value of an expression, `None` if a symbol is not defined yet
+    fn try_evaluate(&self, line: &SourceLine, expr: &str, pc: i64) -> Result<Option<i64>, NesError> {
+        match self.evaluate_expr(line, expr, pc) {
+            Ok(value) => Ok(Some(value)),
+            Err(ExprError::Unknown(_)) => Ok(None),
+            Err(ExprError::Syntax(reason)) => Err(line.error(&reason))
+        }
+    }
+
+    fn evaluate(&self, line: &SourceLine, expr: &str, pc: i64) -> Result<i64, NesError> {
+        self.evaluate_expr(line, expr, pc).map_err(|error| match error {
+            ExprError::Unknown(name) => line.error(&format!("unknown symbol {}", name)),
+            ExprError::Syntax(reason) => line.error(&reason)
+        })
+    }
+
+    fn evaluate_expr(&self, line: &SourceLine, expr: &str, pc: i64) -> Result<i64, ExprError> {
+        let lookup = |name: &str| self.symbols.get(&scoped(&line.scope, name)).copied();
+
+        evaluate(expr, pc, &lookup)
+    }
+
+    fn byte(&self, line: &SourceLine, expr: &str, pc: i64) -> Result<u8, NesError> {
+        let value = self.evaluate(line, expr, pc)?;
+
+        if !(-128..=255).contains(&value) {
+            return Err(line.error(&format!("{} does not fit in a byte", expr)));
+        }
+
+        Ok(value as u8)
+    }
+
+    fn word(&self, line: &SourceLine, expr: &str, pc: i64) -> Result<u16, NesError> {
+        let value = self.evaluate(line, expr, pc)?;
+
+        if !(-0x8000..=0xffff).contains(&value) {
+            return Err(line.error(&format!("{} does not fit in a word", expr)));
+        }
+
+        Ok(value as u16)
+    }
+
+    /// Bytes written by `.org` from `pc` up to `address`
+    fn padding(&self, line: &SourceLine, address: i64, pc: i64) -> Result<i64, NesError> {
+        if address < pc {
+            return Err(line.error(&format!("org ${:04x} is below ${:04x}", address, pc)));
+        }
+
+        Ok(address - pc)
+    }
+
+    fn read(&self, file: &str) -> Result<Vec<u8>, String> {
+        if let Some(data) = self.files.get(file) {
+            return Ok(data.clone());
+        }
+
+        let path = self.directory.join(file);
+
+        fs::read(&path).map_err(|_| format!("cannot read {}", path.display()))
+    }
+
+    fn incbin(&self, line: &SourceLine, file: &str) -> Result<Vec<u8>, NesError> {
+        self.read(file).map_err(|reason| line.error(&reason))
+    }
+
+    /// Opcode of the instruction, zero page is used when the address is known
+    fn choose(
+        &self,
+        line: &SourceLine,
+        mnemonic: &str,
+        operand: &Operand,
+        pc: i64
+    ) -> Result<OpCode, NesError> {
+        let ret = match operand {
+            Operand::None => find_opcode(mnemonic, AddressingMode::Implied)
+                .or_else(|| find_opcode(mnemonic, AddressingMode::Accumulator)),
+            Operand::Accumulator => find_opcode(mnemonic, AddressingMode::Accumulator),
+            Operand::Immediate(_) => find_opcode(mnemonic, AddressingMode::Immediate),
+            Operand::Indirect(_) => find_opcode(mnemonic, AddressingMode::Indirect),
+            Operand::IndexedIndirect(_) => find_opcode(mnemonic, AddressingMode::IndexedIndirect),
+            Operand::IndirectIndexed(_) => find_opcode(mnemonic, AddressingMode::IndirectIndexed),
+            Operand::Address(expr, index) => {
+                let (zero_page, absolute) = match index {
+                    Index::None => (AddressingMode::ZeroPage, AddressingMode::Absolute),
+                    Index::X => (AddressingMode::ZeroPageX, AddressingMode::AbsoluteX),
+                    Index::Y => (AddressingMode::ZeroPageY, AddressingMode::AbsoluteY)
+                };
+                let small = matches!(
+                    self.try_evaluate(line, expr, pc)?,
+                    Some(value) if (0..0x100).contains(&value)
+                );
+                let relative = match index {
+                    Index::None => find_opcode(mnemonic, AddressingMode::Relative),
+                    _ => None
+                };
+                let zero_page = || find_opcode(mnemonic, zero_page);
+                let absolute = || find_opcode(mnemonic, absolute);
+
+                if small {
+                    relative.or_else(zero_page).or_else(absolute)
+                } else {
+                    relative.or_else(absolute).or_else(zero_page)
+                }
+            }
+        };
+
+        ret.ok_or_else(|| line.error(&format!("invalid instruction or operand for {}", mnemonic)))
+    }
+
+    fn operand_bytes(
+        &self,
+        line: &SourceLine,
+        opcode: &OpCode,
+        operand: &Operand,
+        pc: i64
+    ) -> Result<Vec<u8>, NesError> {
+        let expr = match operand {
+            Operand::None | Operand::Accumulator => return Ok(Vec::new()),
+            Operand::Immediate(expr)
+            | Operand::Address(expr, _)
+            | Operand::Indirect(expr)
+            | Operand::IndexedIndirect(expr)
+            | Operand::IndirectIndexed(expr) => expr
+        };
+
+        if opcode.mode == AddressingMode::Relative {
+            let value = self.evaluate(line, expr, pc)?;
+            // Offsets wrap around the address space
+            let offset = (value - (pc + 2)) as u16 as i16;
+
+            if !(-128..=127).contains(&offset) {
+                return Err(line.error(&format!("branch to {} out of range", expr)));
+            }
+
+            return Ok(vec![offset as u8]);
+        }
+
+        match opcode.len {
+            2 => Ok(vec![self.byte(line, expr, pc)?]),
+            _ => Ok(self.word(line, expr, pc)?.to_le_bytes().to_vec())
+        }
+    }
+}
+
+/// Assembling a source file into a NES file
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use nes_utils::assembler::assembler::NesAssembler;
+/// use nes_utils::models::nesutil_model::{Util, Save};
+///
+/// // Writes game.nes and the game.sym symbol map
+/// let mut assembler = NesAssembler::new("src/game.asm");
+/// assembler.run().unwrap();
+/// assembler.save().unwrap();
+/// ```
+pub struct NesAssembler {
+    path: String,
+    assembler: Assembler,
+    bytes: Vec<u8>
+}
+
+impl NesAssembler {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            assembler: Assembler::new(),
+            bytes: Vec::new()
+        }
+    }
+
+    pub fn assembler(&mut self) -> &mut Assembler {
+        &mut self.assembler
+    }
+
+    /// Assembled bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl NesUtil for NesAssembler { }
+
+impl Util for NesAssembler {
+    fn run(&mut self) -> Result<(), NesError> {
+        self.bytes = self.assembler.assemble_file(&self.path)?;
+
+        Ok(())
+    }
+}
+
+impl Save for NesAssembler {
+    /// Write the NES file and the `.sym` symbol map next to it
+    fn save_as(&mut self, path: &str) -> Result<(), NesError> {
+        let sym_path = match path.rsplit_once('.') {
+            Some((stem, _)) if !stem.is_empty() => format!("{}.sym", stem),
+            _ => format!("{}.sym", path)
+        };
+
+        create_and_write_file(&sym_path, self.assembler.symbol_map().as_bytes())?;
+        create_and_write_file(path, &self.bytes)
+    }
+
+    /// Same as `save_as` but with the path stored in the struct.
+    fn save(&mut self) -> Result<(), NesError> {
+        let name = path_to_name(&self.path);
+        let path = format!("./{}.nes", name);
+
+        self.save_as(&path)
+    }
+}
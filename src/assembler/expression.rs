@@ -0,0 +1,219 @@
+/// Why an expression has no value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    Syntax(String),
+    /// The symbol is not defined (yet)
+    Unknown(String)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Symbol(String),
+    /// Address of the current statement, `$` or `*`
+    Pc,
+    Op(&'static str)
+}
+
+/// Binary operators from the lowest to the highest precedence
+const BINARY: [&[&str]; 6] = [
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"]
+];
+
+const OPERATORS: [&str; 15] = [
+    "<<", ">>", "|", "^", "&", "+", "-", "*", "/", "%", "~", "<", ">", "(", ")"
+];
+
+/// Evaluate an expression, `lookup` gives the value of the symbols
+pub fn evaluate(
+    expr: &str,
+    pc: i64,
+    lookup: &dyn Fn(&str) -> Option<i64>
+) -> Result<i64, ExprError> {
+    let tokens = tokenize(expr)?;
+    let mut parser = Parser {
+        tokens: &tokens,
+        position: 0,
+        pc,
+        lookup
+    };
+    let ret = parser.binary(0)?;
+
+    match parser.tokens.get(parser.position) {
+        None => Ok(ret),
+        Some(token) => Err(ExprError::Syntax(format!("unexpected {:?} in {}", token, expr)))
+    }
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>, ExprError> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut ret = Vec::new();
+    let mut n = 0;
+
+    let invalid = || ExprError::Syntax(format!("invalid expression {}", expr));
+
+    while n < chars.len() {
+        let c = chars[n];
+        // A value is expected at the start and after an operator
+        let operand = !matches!(
+            ret.last(),
+            Some(Token::Number(_) | Token::Symbol(_) | Token::Pc | Token::Op(")"))
+        );
+
+        if c.is_whitespace() {
+            n += 1;
+            continue;
+        }
+
+        let (radix, start) = match c {
+            '$' => (16, n + 1),
+            '%' if operand => (2, n + 1),
+            '0'..='9' => (10, n),
+            _ => (0, n)
+        };
+
+        if radix != 0 {
+            let end = (start..chars.len())
+                .find(|&end| !chars[end].is_digit(radix))
+                .unwrap_or(chars.len());
+
+            if end == start {
+                if c != '$' {
+                    return Err(invalid());
+                }
+                ret.push(Token::Pc);
+            } else {
+                let digits: String = chars[start..end].iter().collect();
+                let value = i64::from_str_radix(&digits, radix).map_err(|_| invalid())?;
+
+                ret.push(Token::Number(value));
+            }
+
+            n = end;
+            continue;
+        }
+
+        if c == '\'' {
+            match chars.get(n + 1..n + 3) {
+                Some([value, '\'']) => ret.push(Token::Number(*value as i64)),
+                _ => return Err(invalid())
+            }
+
+            n += 3;
+            continue;
+        }
+
+        if c == '*' && operand {
+            ret.push(Token::Pc);
+            n += 1;
+            continue;
+        }
+
+        if c.is_ascii_alphabetic() || c == '_' || c == '@' {
+            let end = (n + 1..chars.len())
+                .find(|&end| !is_symbol_char(chars[end]))
+                .unwrap_or(chars.len());
+
+            ret.push(Token::Symbol(chars[n..end].iter().collect()));
+            n = end;
+            continue;
+        }
+
+        let rest: String = chars[n..].iter().take(2).collect();
+        let op = OPERATORS
+            .iter()
+            .find(|op| rest.starts_with(*op))
+            .ok_or_else(invalid)?;
+
+        ret.push(Token::Op(op));
+        n += op.len();
+    }
+
+    Ok(ret)
+}
+
+pub fn is_symbol_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+struct Parser<'a> {
+    tokens: &'a [Token],
+    position: usize,
+    pc: i64,
+    lookup: &'a dyn Fn(&str) -> Option<i64>
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<&Token> {
+        let ret = self.tokens.get(self.position);
+
+        self.position += 1;
+        ret
+    }
+
+    /// Binary operators of the `level` precedence and above
+    fn binary(&mut self, level: usize) -> Result<i64, ExprError> {
+        if level == BINARY.len() {
+            return self.unary();
+        }
+
+        let mut ret = self.binary(level + 1)?;
+
+        while let Some(Token::Op(op)) = self.tokens.get(self.position) {
+            if !BINARY[level].contains(op) {
+                break;
+            }
+
+            self.position += 1;
+
+            let right = self.binary(level + 1)?;
+
+            ret = match *op {
+                "|" => ret | right,
+                "^" => ret ^ right,
+                "&" => ret & right,
+                "<<" => ret.wrapping_shl(right as u32),
+                ">>" => ret.wrapping_shr(right as u32),
+                "+" => ret.wrapping_add(right),
+                "-" => ret.wrapping_sub(right),
+                "*" => ret.wrapping_mul(right),
+                _ if right == 0 => return Err(ExprError::Syntax(String::from("division by zero"))),
+                "/" => ret.wrapping_div(right),
+                _ => ret.wrapping_rem(right)
+            };
+        }
+
+        Ok(ret)
+    }
+
+    /// `-`, `~`, and `<` / `>` for the low / high byte
+    fn unary(&mut self) -> Result<i64, ExprError> {
+        let ret = match self.next().cloned() {
+            Some(Token::Op("-")) => self.unary()?.wrapping_neg(),
+            Some(Token::Op("~")) => !self.unary()?,
+            Some(Token::Op("<")) => self.unary()? & 0xff,
+            Some(Token::Op(">")) => (self.unary()? >> 8) & 0xff,
+            Some(Token::Op("(")) => {
+                let ret = self.binary(0)?;
+
+                match self.next() {
+                    Some(Token::Op(")")) => ret,
+                    _ => return Err(ExprError::Syntax(String::from("missing )")))
+                }
+            },
+            Some(Token::Number(value)) => value,
+            Some(Token::Pc) => self.pc,
+            Some(Token::Symbol(name)) => (self.lookup)(&name)
+                .ok_or(ExprError::Unknown(name))?,
+            Some(token) => return Err(ExprError::Syntax(format!("unexpected {:?}", token))),
+            None => return Err(ExprError::Syntax(String::from("missing value")))
+        };
+
+        Ok(ret)
+    }
+}
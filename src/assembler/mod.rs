@@ -0,0 +1,5 @@
+/// Two-pass 6502 assembler
+pub mod assembler;
+
+mod expression;
+mod parser;
@@ -0,0 +1,497 @@
+use std::collections::HashMap;
+
+use crate::utils::error::NesError;
+
+use super::expression::is_symbol_char;
+
+/// Maximum nesting of the includes and macro expansions
+const MAX_DEPTH: usize = 16;
+
+/// Index register of an operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Index {
+    None,
+    X,
+    Y
+}
+
+/// Instruction operand, before the addressing mode is chosen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Operand {
+    None,
+    Accumulator,
+    Immediate(String),
+    Address(String, Index),
+    Indirect(String),
+    IndexedIndirect(String),
+    IndirectIndexed(String)
+}
+
+/// iNES header field set by a `.ines*` directive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InesField {
+    /// 16 KiB units
+    Prg,
+    /// 8 KiB units
+    Chr,
+    Mapper,
+    /// Mirroring, battery and four screen bits of the flags 6
+    Mirroring
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Label(String),
+    Constant(String, String),
+    /// Pad up to the address
+    Org(String),
+    Base(String),
+    Hex(Vec<u8>),
+    Bytes(Vec<String>),
+    Words(Vec<String>),
+    Incbin(String),
+    Ines(InesField, String),
+    Instruction(String, Operand)
+}
+
+/// A statement and where it comes from
+#[derive(Debug, Clone)]
+pub struct SourceLine {
+    pub path: String,
+    pub number: usize,
+    /// Last global label, the local labels (`@name`) belong to it
+    pub scope: String,
+    pub statement: Statement
+}
+
+impl SourceLine {
+    pub fn error(&self, reason: &str) -> NesError {
+        NesError::Assembly {
+            path: self.path.clone(),
+            line: self.number,
+            reason: reason.to_string()
+        }
+    }
+}
+
+/// Symbol name, local labels are prefixed with their scope
+pub fn scoped(scope: &str, name: &str) -> String {
+    match name.starts_with('@') {
+        true => format!("{}{}", scope, name),
+        false => name.to_string()
+    }
+}
+
+struct Macro {
+    path: String,
+    params: Vec<String>,
+    /// Line numbers and text
+    body: Vec<(usize, String)>
+}
+
+/// Source text to statements, with the includes and macros expanded
+pub struct Parser<'a> {
+    /// Content of an included file
+    load: &'a dyn Fn(&str) -> Result<String, String>,
+    macros: HashMap<String, Macro>,
+    expansions: usize,
+    depth: usize,
+    scope: String,
+    lines: Vec<SourceLine>
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(load: &'a dyn Fn(&str) -> Result<String, String>) -> Self {
+        Self {
+            load,
+            macros: HashMap::new(),
+            expansions: 0,
+            depth: 0,
+            scope: String::new(),
+            lines: Vec::new()
+        }
+    }
+
+    pub fn parse(mut self, path: &str, source: &str) -> Result<Vec<SourceLine>, NesError> {
+        self.source(path, source)?;
+
+        Ok(self.lines)
+    }
+
+    fn source(&mut self, path: &str, source: &str) -> Result<(), NesError> {
+        let lines: Vec<(usize, String)> = source
+            .lines()
+            .enumerate()
+            .map(|(n, text)| (n + 1, text.to_string()))
+            .collect();
+
+        self.text(path, &lines)
+    }
+
+    fn text(&mut self, path: &str, lines: &[(usize, String)]) -> Result<(), NesError> {
+        let mut definition: Option<(String, Macro)> = None;
+
+        for (number, text) in lines {
+            let error = |reason: String| NesError::Assembly {
+                path: path.to_string(),
+                line: *number,
+                reason
+            };
+            let text = strip_comment(text).trim();
+
+            // Macro body
+            if let Some((name, mut body)) = definition.take() {
+                let keyword = split_word(text).0.trim_start_matches('.').to_ascii_lowercase();
+
+                if keyword == "endm" || keyword == "endmacro" {
+                    self.macros.insert(name, body);
+                } else {
+                    body.body.push((*number, text.to_string()));
+                    definition = Some((name, body));
+                }
+                continue;
+            }
+
+            let text = self.label(path, *number, text);
+
+            if text.is_empty() {
+                continue;
+            }
+
+            let (word, rest) = split_word(text);
+            let keyword = word.trim_start_matches('.').to_ascii_lowercase();
+
+            match keyword.as_str() {
+                "macro" => {
+                    let (name, params) = split_word(rest);
+
+                    if !is_symbol(name) {
+                        return Err(error(format!("invalid macro name {}", name)));
+                    }
+
+                    definition = Some((name.to_string(), Macro {
+                        path: path.to_string(),
+                        params: split_list(params),
+                        body: Vec::new()
+                    }));
+                },
+                "endm" | "endmacro" => return Err(error(String::from("endm without macro"))),
+                "include" => {
+                    let file = rest.trim_matches('"');
+                    let source = (self.load)(file).map_err(error)?;
+
+                    self.enter().map_err(error)?;
+                    self.source(file, &source)?;
+                    self.depth -= 1;
+                },
+                _ if self.macros.contains_key(word) => {
+                    self.expand(word, rest).map_err(error)??;
+                },
+                _ => {
+                    let statement = parse_statement(word, &keyword, rest).map_err(error)?;
+
+                    self.push(path, *number, statement);
+                }
+            };
+        }
+
+        match definition {
+            Some((name, _)) => Err(NesError::Assembly {
+                path: path.to_string(),
+                line: lines.last().map_or(0, |(number, _)| *number),
+                reason: format!("macro {} without endm", name)
+            }),
+            None => Ok(())
+        }
+    }
+
+    fn push(&mut self, path: &str, number: usize, statement: Statement) {
+        self.lines.push(SourceLine {
+            path: path.to_string(),
+            number,
+            scope: self.scope.clone(),
+            statement
+        });
+    }
+
+    /// Push the label starting the line, return the rest of the line
+    fn label<'t>(&mut self, path: &str, number: usize, text: &'t str) -> &'t str {
+        let (name, rest) = match text.split_once(':') {
+            Some((name, rest)) if is_label(name) => (name, rest.trim()),
+            _ => return text
+        };
+
+        if !name.starts_with('@') {
+            self.scope = name.to_string();
+        }
+
+        let statement = Statement::Label(scoped(&self.scope, name));
+
+        self.push(path, number, statement);
+
+        rest
+    }
+
+    fn enter(&mut self) -> Result<(), String> {
+        if self.depth == MAX_DEPTH {
+            return Err(String::from("too many nested includes or macros"));
+        }
+
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Expand a macro, its local labels are not visible outside.
+    /// The outer error is about the invocation, the inner one about the body.
+    fn expand(&mut self, name: &str, args: &str) -> Result<Result<(), NesError>, String> {
+        let definition = &self.macros[name];
+        let args = split_list(args);
+
+        if args.len() != definition.params.len() {
+            return Err(format!(
+                "macro {} expects {} arguments, got {}",
+                name,
+                definition.params.len(),
+                args.len()
+            ));
+        }
+
+        let path = definition.path.clone();
+        let body: Vec<(usize, String)> = definition.body
+            .iter()
+            .map(|(number, text)| (*number, substitute(text, &definition.params, &args)))
+            .collect();
+
+        let scope = self.scope.clone();
+
+        self.scope = format!("{}#{}", scope, self.expansions);
+        self.expansions += 1;
+        self.enter()?;
+
+        let ret = self.text(&path, &body);
+
+        self.depth -= 1;
+        self.scope = scope;
+
+        Ok(ret)
+    }
+}
+
+/// Remove the comment, `;` in quotes are kept
+fn strip_comment(text: &str) -> &str {
+    let mut quoted = false;
+
+    for (n, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => return &text[..n],
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// First word and the rest of the line
+fn split_word(text: &str) -> (&str, &str) {
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (text, "")
+    }
+}
+
+fn parse_statement(word: &str, keyword: &str, rest: &str) -> Result<Statement, String> {
+    // Constant
+    if let Some((name, value)) = rest.split_once(char::is_whitespace) {
+        if name.eq_ignore_ascii_case("equ") || name == "=" {
+            return Ok(Statement::Constant(word.to_string(), value.trim().to_string()));
+        }
+    }
+    if let Some((name, value)) = format!("{} {}", word, rest).split_once('=') {
+        if is_symbol(name.trim()) {
+            return Ok(Statement::Constant(name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let ret = match keyword {
+        "org" => Statement::Org(rest.to_string()),
+        "base" => Statement::Base(rest.to_string()),
+        "hex" => Statement::Hex(parse_hex(rest)?),
+        "byte" | "db" => Statement::Bytes(parse_bytes(rest)?),
+        "word" | "dw" => Statement::Words(split_list(rest)),
+        "incbin" => Statement::Incbin(rest.trim_matches('"').to_string()),
+        "inesprg" => Statement::Ines(InesField::Prg, rest.to_string()),
+        "ineschr" => Statement::Ines(InesField::Chr, rest.to_string()),
+        "inesmap" => Statement::Ines(InesField::Mapper, rest.to_string()),
+        "inesmir" => Statement::Ines(InesField::Mirroring, rest.to_string()),
+        _ if word.starts_with('.') => return Err(format!("unknown directive {}", word)),
+        _ => Statement::Instruction(keyword.to_string(), parse_operand(rest)?)
+    };
+
+    Ok(ret)
+}
+
+fn is_symbol(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(is_symbol_char)
+}
+
+/// Global or local (`@name`) label
+fn is_label(name: &str) -> bool {
+    is_symbol(name.strip_prefix('@').unwrap_or(name))
+}
+
+/// Comma separated values, commas in quotes are kept
+fn split_list(text: &str) -> Vec<String> {
+    let mut ret = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (n, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            ',' if !quoted => {
+                ret.push(text[start..n].trim().to_string());
+                start = n + 1;
+            },
+            _ => {}
+        }
+    }
+
+    ret.push(text[start..].trim().to_string());
+    ret.retain(|value| !value.is_empty());
+    ret
+}
+
+/// Replace the macro parameters with the arguments
+fn substitute(text: &str, params: &[String], args: &[String]) -> String {
+    let mut ret = String::new();
+    let mut word = String::new();
+
+    for c in text.chars().chain(std::iter::once('\n')) {
+        if is_symbol_char(c) {
+            word.push(c);
+            continue;
+        }
+
+        match params.iter().position(|param| *param == word) {
+            Some(n) if !ret.ends_with('@') => ret.push_str(&args[n]),
+            _ => ret.push_str(&word)
+        }
+
+        word.clear();
+        ret.push(c);
+    }
+
+    ret.pop();
+    ret
+}
+
+/// `hex 4e 45 53 1a`
+fn parse_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: String = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    if !digits.len().is_multiple_of(2) {
+        return Err(format!("odd number of digits in hex {}", text));
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|n| u8::from_str_radix(&digits[n..n + 2], 16)
+            .map_err(|_| format!("invalid hex {}", text)))
+        .collect()
+}
+
+/// Byte expressions, a string gives one byte per character
+fn parse_bytes(text: &str) -> Result<Vec<String>, String> {
+    let mut ret = Vec::new();
+
+    for value in split_list(text) {
+        match value.strip_prefix('"') {
+            Some(string) => {
+                let string = string
+                    .strip_suffix('"')
+                    .ok_or_else(|| format!("unterminated string {}", value))?;
+
+                ret.extend(string.bytes().map(|byte| byte.to_string()));
+            },
+            None => ret.push(value)
+        }
+    }
+
+    Ok(ret)
+}
+
+/// Index of the `)` closing the `(` at the start of the text
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+
+    for (n, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(n);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn parse_operand(text: &str) -> Result<Operand, String> {
+    let text = text.trim();
+    let invalid = || format!("invalid operand {}", text);
+
+    if text.is_empty() {
+        return Ok(Operand::None);
+    }
+    if text.eq_ignore_ascii_case("a") {
+        return Ok(Operand::Accumulator);
+    }
+    if let Some(value) = text.strip_prefix('#') {
+        return Ok(Operand::Immediate(value.trim().to_string()));
+    }
+
+    // (addr), (zp, x) and (zp), y, a parenthesized expression is an address
+    if text.starts_with('(') {
+        let end = closing_paren(text).ok_or_else(invalid)?;
+        let (inner, after) = (text[1..end].trim(), text[end + 1..].trim());
+
+        let ret = match (after.strip_prefix(','), inner.rsplit_once(',')) {
+            (None, None) if after.is_empty() => Some(Operand::Indirect(inner.to_string())),
+            (None, Some((value, index))) if after.is_empty() && is_index(index, "x") => {
+                Some(Operand::IndexedIndirect(value.trim().to_string()))
+            },
+            (Some(index), None) if is_index(index, "y") => {
+                Some(Operand::IndirectIndexed(inner.to_string()))
+            },
+            (None, None) => None,
+            _ => return Err(invalid())
+        };
+
+        if let Some(ret) = ret {
+            return Ok(ret);
+        }
+    }
+
+    let ret = match text.rsplit_once(',') {
+        Some((value, index)) if is_index(index, "x") => Operand::Address(value.trim().to_string(), Index::X),
+        Some((value, index)) if is_index(index, "y") => Operand::Address(value.trim().to_string(), Index::Y),
+        Some(_) => return Err(invalid()),
+        None => Operand::Address(text.to_string(), Index::None)
+    };
+
+    Ok(ret)
+}
+
+fn is_index(text: &str, register: &str) -> bool {
+    text.trim().eq_ignore_ascii_case(register)
+}
@@ -1,14 +1,13 @@
+use std::num::NonZeroUsize;
+
 use crate::{
-    models::{
-        nesutil_model::{
-            Save,
-            Util, NesUtil
-        },
-        header_model::Header
+    models::nesutil_model::{
+        Save,
+        Util, NesUtil
     },
     disassembler::header::NesHeader,
+    rom::rom::NesRom,
     utils::{
-        block::Block,
         util::{
             path_to_name
         },
@@ -18,80 +17,59 @@ use crate::{
 
 use super::image::NesImage;
 
+/// Size of a pattern table, drawn as one image
+const BANK_SIZE: NonZeroUsize = NonZeroUsize::new(NesHeader::CHR_ROM_BANK_SIZE).unwrap();
+
 /// Interacting with the CHR ROM 
 ///
 /// # Examples
 ///
 /// Basic usage:
 ///
-/// ```
+/// ```no_run
 /// use nes_utils::chr::chr::NesChr;
 /// use nes_utils::models::nesutil_model::{Util, Save};
-/// 
-/// use std::fs::File;
-/// use std::io::Read;
-/// 
-/// let path = String::from("games/game.nes");
-/// let mut mem = Vec::<u8>::new();
-/// let mut f = File::open(&path).unwrap();
+/// use nes_utils::rom::rom::NesRom;
 ///
-/// f.read_to_end(&mut mem);
+/// let rom = NesRom::from_file("games/game.nes").unwrap();
 ///
-/// let mut chr = NesChr::new(&path, &mem);
-/// chr.run();
-/// chr.save();
+/// let mut chr = NesChr::new(&rom);
+/// chr.run().unwrap();
+/// chr.save().unwrap();
 /// ```
-pub struct NesChr {
-    path: String,
-    header: NesHeader,
-    chr_rom: Block,
-    mem: Vec<u8>,
+pub struct NesChr<'a> {
+    rom: &'a NesRom,
     images: Vec<NesImage>
 }
 
-impl NesChr {
-    pub fn new(path: &String, mem: &Vec<u8>) -> Self {
+impl<'a> NesChr<'a> {
+    pub fn new(rom: &'a NesRom) -> Self {
         Self {
-            path: String::from(path),
-            header: NesHeader::new(&mem),
-            chr_rom: Block::new(0, 0),
-            mem: mem.to_vec(),
+            rom,
             images: Vec::new()
         }
     }
 
-    fn parse(&mut self) -> &Self {
-        self.header.parse();
-        
-        // Get header metadata
-        let prg_rom_size = self.header.field("len_prg_rom").value.unwrap()[0] as usize * NesHeader::PRG_ROM_UNIT_SIZE;
-        self.chr_rom.size = self.header.field("len_chr_rom").value.unwrap()[0] as usize * NesHeader::CHR_ROM_UNIT_SIZE;
-
-        // Check if there is the trainer (512 bytes)
-        self.chr_rom.pos = NesHeader::HEADER_SIZE;
-        if self.header.is_trainer() {
-            self.chr_rom.pos += NesHeader::TRAINER_SIZE;
-        }
-
-        self.chr_rom.pos += prg_rom_size;
+    fn chr_to_img(&mut self) -> Result<(), NesError> {
+        let expected = self.rom.header().chr_rom_size();
+        let actual = self.rom.chr_rom().len();
 
-        // Fill the blocks
-        self.chr_rom.value_from(&self.mem);
-
-        self
-    }
-
-    fn chr_to_img(&mut self) {
-
-        let chr_mem = self.chr_rom.value.as_ref().unwrap();
+        if actual < expected {
+            return Err(NesError::UnexpectedSize {
+                offset: self.rom.chr_rom_offset(),
+                expected,
+                actual
+            });
+        }
 
-        for n in 0..2 {
+        let banks = self.rom
+            .chr_banks(BANK_SIZE)
+            .take(2);
 
-            let idx = n * NesHeader::CHR_ROM_BANK_SIZE;
-            let bank = &chr_mem[idx..idx + NesHeader::CHR_ROM_BANK_SIZE];
+        for (n, bank) in banks.enumerate() {
             let path = format!(
                 "{}{}.png",
-                path_to_name(&self.path),
+                path_to_name(self.rom.path()),
                 n
             );
                         
@@ -100,44 +78,43 @@ impl NesChr {
     
             self.images.push(image);
         }
+
+        Ok(())
     }
 }
 
-impl NesUtil for NesChr { }
+impl NesUtil for NesChr<'_> { }
 
-impl Save for NesChr {
+impl Save for NesChr<'_> {
     /// Save pixels to the path as argument.
     /// 
     /// Dump the two CHR banks into two PNG files
-    fn save_as(&mut self, path: &str) {
-        let mut n = 0;
-
-        for image in &mut self.images {
+    fn save_as(&mut self, path: &str) -> Result<(), NesError> {
+        for (n, image) in self.images.iter_mut().enumerate() {
             let path = format!(
                 "{}{}.png",
-                path_to_name(&path),
+                path_to_name(path),
                 n
             );
-            image.save_as(&path);
-            n += 1;
+            image.save_as(&path)?;
         }
+
+        Ok(())
     }
 
     /// Same as `save_as` but with the path stored in the struct.
-    fn save(&mut self) {
-        self.save_as(&self.path.clone());
+    fn save(&mut self) -> Result<(), NesError> {
+        self.save_as(self.rom.path())
     }
 }
 
-impl Util for NesChr {
+impl Util for NesChr<'_> {
     /// Parse the CHR ROM and dump images into PNGs
-    fn run(&mut self) {
-        self.parse();
-
-        if self.header.is_chr() == false {
-            panic!("{}", NesError::MissingChr)
+    fn run(&mut self) -> Result<(), NesError> {
+        if !self.rom.header().is_chr() {
+            return Err(NesError::MissingChr);
         }
 
-        self.chr_to_img();
+        self.chr_to_img()
     }
 }
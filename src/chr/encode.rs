@@ -4,7 +4,8 @@ use crate::{
         Util,
         Save
     },
-    disassembler::header::NesHeader
+    disassembler::header::NesHeader,
+    utils::error::NesError
 };
 
 #[allow(dead_code)]
@@ -23,33 +24,32 @@ pub struct NesChrEncode {
 }
 
 impl NesChrEncode {
-    pub fn new(path: &String) -> Self {
+    pub fn new(path: &String) -> Result<Self, NesError> {
+        let img = image::open(path)?;
+        let rgb_data = img.to_rgb8().as_raw().to_vec();
 
-        let img = image::open(path);
-        let rgb_data = img.unwrap().to_rgb8().as_raw().to_vec();
-        
-        Self {
+        Ok(Self {
             path: path.to_string(),
             rgb_data,
             mem: [0x00; NesHeader::CHR_ROM_UNIT_SIZE]
-        }
+        })
     }
 }
 
 impl NesUtil for NesChrEncode { }
 
 impl Util for NesChrEncode {
-    fn run(&mut self) {
+    fn run(&mut self) -> Result<(), NesError> {
         todo!()
     }
 }
 
 impl Save for NesChrEncode {
-    fn save(&mut self) {
+    fn save(&mut self) -> Result<(), NesError> {
         todo!()
     }
 
-    fn save_as(&mut self, _path: &str) {
+    fn save_as(&mut self, _path: &str) -> Result<(), NesError> {
         todo!()
     }
 }
@@ -2,7 +2,10 @@ extern crate image;
 
 use std::path::Path;
 
-use crate::models::nesutil_model::Save;
+use crate::{
+    models::nesutil_model::Save,
+    utils::error::NesError
+};
 
 type Rgb = (u8, u8, u8);
 
@@ -72,11 +75,11 @@ impl NesImage {
 }
 
 impl Save for NesImage {
-    fn save(&mut self) {
-        self.save_as(&self.path.clone());
+    fn save(&mut self) -> Result<(), NesError> {
+        self.save_as(&self.path.clone())
     }
 
-    fn save_as(&mut self, path: &str) {
+    fn save_as(&mut self, path: &str) -> Result<(), NesError> {
         let mut buffer: Vec<u8> = Vec::new();
 
         for pixel in &self.mem {
@@ -88,11 +91,13 @@ impl Save for NesImage {
         }
 
         image::save_buffer(
-            &Path::new(&path),
+            Path::new(&path),
             &buffer,
             NesImage::W as u32,
             NesImage::H as u32,
             image::ColorType::Rgb8
-        ).expect("Unable to dump this bank");
+        )?;
+
+        Ok(())
     }
 }
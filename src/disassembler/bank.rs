@@ -0,0 +1,133 @@
+use crate::{
+    mapper::mapper::{
+        get_mapper,
+        Mapper
+    },
+    rom::rom::NesRom
+};
+
+/// Mapper used for the bank layout of unknown mappers (UxROM like)
+const DEFAULT_MAPPER: u16 = 2;
+
+/// A PRG ROM bank and the CPU address it is mapped at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrgBank {
+    pub index: usize,
+    /// Offset of the bank in the PRG ROM
+    pub offset: usize,
+    pub size: usize,
+    /// CPU address of the first byte of the bank
+    pub address: u16,
+    /// Always mapped at `address` (not switchable)
+    pub fixed: bool
+}
+
+impl PrgBank {
+    /// CPU address of a PRG ROM offset inside the bank
+    pub fn address_of(&self, offset: usize) -> u16 {
+        self.address.wrapping_add((offset - self.offset) as u16)
+    }
+
+    /// PRG ROM offset of a CPU address inside the bank
+    pub fn offset_of(&self, address: u16) -> Option<usize> {
+        if !self.contains(address) {
+            return None;
+        }
+
+        Some(self.offset + (address - self.address) as usize)
+    }
+
+    /// Return true if the CPU address is inside the bank
+    pub fn contains(&self, address: u16) -> bool {
+        let address = address as usize;
+        let start = self.address as usize;
+
+        address >= start && address < start + self.size
+    }
+
+    /// Return true if the PRG ROM offset is inside the bank
+    pub fn contains_offset(&self, offset: usize) -> bool {
+        offset >= self.offset && offset < self.offset + self.size
+    }
+}
+
+/// Split the PRG ROM into the banks of the ROM mapper.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::bank::prg_banks;
+/// use nes_utils::rom::rom::NesRom;
+///
+/// // UxROM, 4 banks of 16 KiB
+/// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x04, 0x00, 0x20];
+/// mem.resize(16 + 0x10000, 0x00);
+///
+/// let rom = NesRom::new("game.nes", &mem).unwrap();
+/// let banks = prg_banks(&rom);
+///
+/// assert_eq!(banks.len(), 4);
+/// assert_eq!(banks[0].address, 0x8000);
+/// assert_eq!(banks[3].address, 0xc000);
+/// ```
+pub fn prg_banks(rom: &NesRom) -> Vec<PrgBank> {
+    let mapper: &Mapper = match rom.mapper().or_else(|| get_mapper(DEFAULT_MAPPER)) {
+        Some(mapper) => mapper,
+        None => return Vec::new()
+    };
+    let prg = rom.prg_rom();
+    let count = mapper.prg_bank_count(prg.len());
+
+    prg
+        .chunks(mapper.prg_bank_size)
+        .enumerate()
+        .map(|(index, bank)| {
+            let address = mapper.prg_bank_address(index, count);
+
+            PrgBank {
+                index,
+                offset: index * mapper.prg_bank_size,
+                size: bank.len(),
+                address,
+                fixed: mapper.fixed_prg_bank(address, prg.len()) == Some(index)
+            }
+        })
+        .collect()
+}
+
+/// PRG bank of a CPU address seen from the bank `from`.
+///
+/// The current bank wins, then the fixed banks, then the only bank
+/// mapped at this address if there is a single one.
+pub fn resolve_bank(banks: &[PrgBank], from: Option<usize>, address: u16) -> Option<&PrgBank> {
+    let current = from
+        .and_then(|index| banks.get(index))
+        .filter(|bank| bank.contains(address));
+    let fixed = || banks
+        .iter()
+        .find(|bank| bank.fixed && bank.contains(address));
+    let only = || {
+        let mut candidates = banks
+            .iter()
+            .filter(|bank| bank.contains(address));
+
+        match (candidates.next(), candidates.next()) {
+            (Some(bank), None) => Some(bank),
+            _ => None
+        }
+    };
+
+    current
+        .or_else(fixed)
+        .or_else(only)
+}
+
+/// Return true if several banks may be mapped at the CPU address
+pub fn is_ambiguous(banks: &[PrgBank], address: u16) -> bool {
+    banks
+        .iter()
+        .filter(|bank| bank.contains(address))
+        .count() > 1
+}
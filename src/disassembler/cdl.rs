@@ -0,0 +1,153 @@
+use std::fs;
+
+use crate::{
+    rom::rom::NesRom,
+    utils::error::NesError
+};
+
+/// Magic of the Mesen CDL files, FCEUX files have no header
+const MESEN_MAGIC: &[u8] = b"CDLv2";
+
+/// Size of a CHR tile
+const TILE_SIZE: usize = 16;
+
+/// How a PRG ROM byte has been used while logging
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CdlUsage {
+    /// Never executed nor read
+    Unused,
+    Code,
+    Data,
+    /// Read through a pointer (`lda (ptr), y`, `jmp (ptr)`)
+    IndirectData,
+    /// Read by the DMC channel
+    PcmData
+}
+
+impl CdlUsage {
+    /// Short description, empty for the common code and data
+    pub fn note(&self) -> &'static str {
+        match self {
+            Self::Unused => "unused",
+            Self::IndirectData => "indirect",
+            Self::PcmData => "dmc",
+            Self::Code | Self::Data => ""
+        }
+    }
+}
+
+/// Code/Data Logger file of FCEUX or Mesen, one byte of flags
+/// for each PRG ROM and CHR ROM byte
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::cdl::{Cdl, CdlUsage};
+/// use nes_utils::rom::rom::NesRom;
+///
+/// // NROM-128 without CHR ROM
+/// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00];
+/// mem.resize(16 + 0x4000, 0x00);
+///
+/// let rom = NesRom::new("game.nes", &mem).unwrap();
+///
+/// let mut log = vec![0x00; 0x4000];
+/// log[0] = Cdl::CODE;
+/// log[1] = Cdl::DATA | Cdl::INDIRECT_DATA;
+///
+/// let cdl = Cdl::new(&rom, &log).unwrap();
+///
+/// assert_eq!(cdl.usage(0), CdlUsage::Code);
+/// assert_eq!(cdl.usage(1), CdlUsage::IndirectData);
+/// assert_eq!(cdl.usage(2), CdlUsage::Unused);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cdl {
+    prg: Vec<u8>,
+    chr: Vec<u8>
+}
+
+impl Cdl {
+    /// Executed PRG byte, drawn CHR byte
+    pub const CODE: u8 = 0x01;
+    /// Read PRG byte, read CHR byte
+    pub const DATA: u8 = 0x02;
+    pub const INDIRECT_CODE: u8 = 0x10;
+    pub const INDIRECT_DATA: u8 = 0x20;
+    pub const PCM_DATA: u8 = 0x40;
+
+    /// Parse the log of a ROM, with or without the Mesen header
+    pub fn new(rom: &NesRom, bytes: &[u8]) -> Result<Self, NesError> {
+        let prg_size = rom.prg_rom().len();
+        let size = prg_size + rom.chr_rom().len();
+
+        let data = match bytes.strip_prefix(MESEN_MAGIC) {
+            // Mesen 2 stores a CRC32 of the ROM after the magic
+            Some(data) if data.len() == size + 4 => &data[4..],
+            Some(data) => data,
+            None => bytes
+        };
+
+        if data.len() != size {
+            return Err(NesError::UnexpectedSize {
+                offset: bytes.len() - data.len(),
+                expected: size,
+                actual: data.len()
+            });
+        }
+
+        Ok(Self {
+            prg: data[..prg_size].to_vec(),
+            chr: data[prg_size..].to_vec()
+        })
+    }
+
+    pub fn from_file(rom: &NesRom, path: &str) -> Result<Self, NesError> {
+        let bytes = fs::read(path)
+            .map_err(|source| NesError::FileInvalid {
+                path: path.to_string(),
+                source
+            })?;
+
+        Self::new(rom, &bytes)
+    }
+
+    /// Flags of a PRG ROM byte
+    pub fn prg_flags(&self, offset: usize) -> u8 {
+        self.prg.get(offset).copied().unwrap_or(0)
+    }
+
+    /// How a PRG ROM byte has been used, code wins over data
+    pub fn usage(&self, offset: usize) -> CdlUsage {
+        let flags = self.prg_flags(offset);
+
+        if flags & Self::CODE != 0 {
+            CdlUsage::Code
+        } else if flags & Self::PCM_DATA != 0 {
+            CdlUsage::PcmData
+        } else if flags & Self::INDIRECT_DATA != 0 {
+            CdlUsage::IndirectData
+        } else if flags & Self::DATA != 0 {
+            CdlUsage::Data
+        } else {
+            CdlUsage::Unused
+        }
+    }
+
+    /// Return true if a byte of the tile has been drawn or read
+    pub fn is_tile_used(&self, tile: usize) -> bool {
+        self.chr
+            .chunks(TILE_SIZE)
+            .nth(tile)
+            .is_some_and(|flags| flags.iter().any(|flags| flags & (Self::CODE | Self::DATA) != 0))
+    }
+
+    /// CHR ROM tiles never drawn nor read
+    pub fn unused_tiles(&self) -> Vec<usize> {
+        (0..self.chr.len() / TILE_SIZE)
+            .filter(|tile| !self.is_tile_used(*tile))
+            .collect()
+    }
+}
@@ -0,0 +1,482 @@
+use crate::{
+    disassembler::{
+        bank::PrgBank,
+        header::NesHeader,
+        header_info::HeaderFormat
+    },
+    rom::rom::NesRom,
+    utils::addressing::AddressingMode
+};
+
+/// NESASM bank size
+const NESASM_BANK_SIZE: usize = 0x2000;
+
+/// Assembler syntax of the disassembly output
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use nes_utils::disassembler::dialect::Ca65;
+/// use nes_utils::disassembler::disassembler::NesDisassembler;
+/// use nes_utils::models::nesutil_model::{Util, Save};
+/// use nes_utils::rom::rom::NesRom;
+///
+/// let rom = NesRom::from_file("games/game.nes").unwrap();
+///
+/// // Writes game.asm, game.chr and the game.cfg linker config
+/// let mut d = NesDisassembler::new(&rom);
+/// d.set_dialect(Ca65);
+/// d.run().unwrap();
+/// d.save().unwrap();
+/// ```
+pub trait Dialect {
+    fn name(&self) -> &'static str;
+
+    /// Return true if the unofficial opcodes can be assembled
+    fn unofficial(&self) -> bool {
+        false
+    }
+
+    /// Directives written before anything else
+    fn prologue(&self) -> String {
+        String::new()
+    }
+
+    fn constant(&self, name: &str, value: u16) -> String {
+        format!("{} = ${:04x}", name, value)
+    }
+
+    fn header(&self, rom: &NesRom) -> String {
+        self.data(rom.header().bytes())
+    }
+
+    /// Parts of the ROM the assembler cannot write, the source does
+    /// not assemble to the ROM if there is any
+    fn unsupported(&self, _rom: &NesRom) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Trainer bytes, a comment if the assembler cannot write it
+    fn trainer(&self, trainer: &[u8]) -> String {
+        trainer
+            .chunks(16)
+            .map(|bytes| format!("{}{}", self.indent(), self.data(bytes)))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Directives starting a PRG bank
+    fn prg_bank(&self, bank: &PrgBank) -> String;
+
+    /// Directives ending a PRG bank
+    fn prg_bank_end(&self, _bank: &PrgBank) -> String {
+        String::new()
+    }
+
+    /// Directives including the CHR ROM from a binary file
+    fn chr(&self, rom: &NesRom, path: &str) -> String;
+
+    /// Bytes after the CHR ROM (misc ROM, trailing data), a comment
+    /// if the assembler cannot write them
+    fn extra(&self, bytes: &[u8]) -> String {
+        bytes
+            .chunks(16)
+            .map(|bytes| format!("{}{}", self.indent(), self.data(bytes)))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Size of the banks if the assembler splits the PRG ROM itself
+    fn bank_size(&self) -> Option<usize> {
+        None
+    }
+
+    fn label(&self, name: &str) -> String {
+        format!("{}:", name)
+    }
+
+    /// Written before the instructions and data
+    fn indent(&self) -> &'static str {
+        ""
+    }
+
+    /// Data bytes
+    fn data(&self, bytes: &[u8]) -> String {
+        format!(".byte {}", byte_list(bytes))
+    }
+
+    /// Address of a table of words, a label or an expression
+    fn word(&self, value: &str) -> String {
+        format!(".word {}", value)
+    }
+
+    /// Address stored high byte first
+    fn word_big_endian(&self, value: &str) -> String {
+        format!(".byte >({0}), <({0})", value)
+    }
+
+    /// Undecodable bytes
+    fn bytes(&self, bytes: &[u8]) -> String {
+        self.data(bytes)
+    }
+
+    /// Operand in the dialect syntax, `absolute` is set for a zero page
+    /// address with an absolute mode. `None` if it cannot be written.
+    fn operand(&self, mode: AddressingMode, arg: &str, absolute: bool) -> Option<String> {
+        let _ = mode;
+
+        (!absolute).then(|| arg.to_string())
+    }
+
+    /// Linker configuration written next to the source
+    fn linker_config(&self, _rom: &NesRom, _banks: &[PrgBank]) -> Option<String> {
+        None
+    }
+}
+
+/// `$xx, $xx, ...`
+fn byte_list(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("${:02x}", byte))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// asm6, or asm6f with the unofficial opcodes
+pub struct Asm6 {
+    unofficial: bool
+}
+
+impl Asm6 {
+    pub fn new(unofficial: bool) -> Self {
+        Self { unofficial }
+    }
+}
+
+impl Default for Asm6 {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+impl Dialect for Asm6 {
+    fn name(&self) -> &'static str {
+        if self.unofficial { "asm6f" } else { "asm6" }
+    }
+
+    fn unofficial(&self) -> bool {
+        self.unofficial
+    }
+
+    fn constant(&self, name: &str, value: u16) -> String {
+        format!("{} equ ${:02x?}", name, value)
+    }
+
+    fn header(&self, rom: &NesRom) -> String {
+        format!("{}", rom.header())
+    }
+
+    fn prg_bank(&self, bank: &PrgBank) -> String {
+        format!(".base ${:04x}", bank.address)
+    }
+
+    fn chr(&self, _rom: &NesRom, path: &str) -> String {
+        format!(".incbin {}", path)
+    }
+
+    fn data(&self, bytes: &[u8]) -> String {
+        let bytes: Vec<String> = bytes
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+
+        format!("hex {}", bytes.join(" "))
+    }
+
+    fn bytes(&self, bytes: &[u8]) -> String {
+        format!(".byte {}", byte_list(bytes))
+    }
+}
+
+/// ca65, linked with the generated configuration
+pub struct Ca65;
+
+impl Ca65 {
+    fn segment(bank: &PrgBank) -> String {
+        format!("PRG{}", bank.index)
+    }
+}
+
+impl Dialect for Ca65 {
+    fn name(&self) -> &'static str {
+        "ca65"
+    }
+
+    fn unofficial(&self) -> bool {
+        true
+    }
+
+    fn prologue(&self) -> String {
+        String::from(".setcpu \"6502X\"")
+    }
+
+    fn header(&self, rom: &NesRom) -> String {
+        format!(".segment \"HEADER\"\n\n{}", self.data(rom.header().bytes()))
+    }
+
+    fn trainer(&self, trainer: &[u8]) -> String {
+        let data: Vec<String> = trainer
+            .chunks(16)
+            .map(|bytes| self.data(bytes))
+            .collect();
+
+        format!(".segment \"TRAINER\"\n\n{}", data.join("\n"))
+    }
+
+    fn prg_bank(&self, bank: &PrgBank) -> String {
+        format!(".segment \"{}\"", Self::segment(bank))
+    }
+
+    fn chr(&self, _rom: &NesRom, path: &str) -> String {
+        format!(".segment \"CHR\"\n\n.incbin \"{}\"", path)
+    }
+
+    fn extra(&self, bytes: &[u8]) -> String {
+        let data: Vec<String> = bytes
+            .chunks(16)
+            .map(|bytes| self.data(bytes))
+            .collect();
+
+        format!(".segment \"EXTRA\"\n\n{}", data.join("\n"))
+    }
+
+    fn operand(&self, _mode: AddressingMode, arg: &str, absolute: bool) -> Option<String> {
+        if absolute {
+            return Some(format!("a:{}", arg));
+        }
+
+        Some(arg.to_string())
+    }
+
+    fn linker_config(&self, rom: &NesRom, banks: &[PrgBank]) -> Option<String> {
+        let mut memory = vec![
+            String::from("    HEADER: start = $0000, size = $0010, file = %O, fill = yes;")
+        ];
+        let mut segments = vec![
+            String::from("    HEADER: load = HEADER, type = ro;")
+        ];
+
+        if let Some(trainer) = rom.trainer() {
+            memory.push(format!(
+                "    TRAINER: start = $7000, size = ${:04x}, file = %O, fill = yes;",
+                trainer.len()
+            ));
+            segments.push(String::from("    TRAINER: load = TRAINER, type = ro;"));
+        }
+
+        for bank in banks {
+            let name = Self::segment(bank);
+
+            memory.push(format!(
+                "    {}: start = ${:04x}, size = ${:04x}, file = %O, fill = yes;",
+                name, bank.address, bank.size
+            ));
+            segments.push(format!("    {}: load = {}, type = ro;", name, name));
+        }
+
+        if !rom.chr_rom().is_empty() {
+            memory.push(format!(
+                "    CHR: start = $0000, size = ${:04x}, file = %O, fill = yes;",
+                rom.chr_rom().len()
+            ));
+            segments.push(String::from("    CHR: load = CHR, type = ro;"));
+        }
+
+        // Misc ROM and trailing data
+        let extra = rom.misc_rom().len() + rom.trailing_data().len();
+
+        if extra > 0 {
+            memory.push(format!(
+                "    EXTRA: start = $0000, size = ${:04x}, file = %O, fill = yes;",
+                extra
+            ));
+            segments.push(String::from("    EXTRA: load = EXTRA, type = ro;"));
+        }
+
+        Some(format!(
+            "MEMORY {{\n{}\n}}\n\nSEGMENTS {{\n{}\n}}\n",
+            memory.join("\n"),
+            segments.join("\n")
+        ))
+    }
+}
+
+/// NESASM 3, with 8 KiB banks
+pub struct Nesasm;
+
+impl Dialect for Nesasm {
+    fn name(&self) -> &'static str {
+        "nesasm"
+    }
+
+    fn constant(&self, name: &str, value: u16) -> String {
+        format!("{} .equ ${:04x}", name, value)
+    }
+
+    fn header(&self, rom: &NesRom) -> String {
+        let header = rom.header();
+
+        // Mirroring, battery and four screen bits of the flags 6,
+        // the trainer cannot be written
+        let mirroring = header.bytes()[6] & 0x0b;
+
+        format!(
+            "  .inesprg {}\n  .ineschr {}\n  .inesmap {}\n  .inesmir {}",
+            header.prg_rom_size() / 0x4000,
+            header.chr_rom_size() / 0x2000,
+            header.mapper(),
+            mirroring
+        )
+    }
+
+    /// The ROM size is given by `.inesprg` and `.ineschr`, only the mapper
+    /// and the flags 6 are written in the header
+    fn unsupported(&self, rom: &NesRom) -> Vec<&'static str> {
+        let header = rom.header();
+        let bytes = header.bytes();
+        let mut ret = Vec::new();
+
+        if header.format() == HeaderFormat::Nes2 {
+            ret.push("NES 2.0 header");
+        }
+        if bytes[7] & 0x0f != 0 || bytes[8..NesHeader::HEADER_SIZE].iter().any(|byte| *byte != 0) {
+            ret.push("header bytes 7-15");
+        }
+        if !header.prg_rom_size().is_multiple_of(NesHeader::PRG_ROM_UNIT_SIZE)
+            || !header.chr_rom_size().is_multiple_of(NesHeader::CHR_ROM_UNIT_SIZE) {
+            ret.push("ROM size not a multiple of 16K/8K");
+        }
+        if rom.trainer().is_some() {
+            ret.push("trainer");
+        }
+        if !rom.misc_rom().is_empty() {
+            ret.push("misc ROM");
+        }
+        if !rom.trailing_data().is_empty() {
+            ret.push("trailing data");
+        }
+
+        ret
+    }
+
+    fn trainer(&self, trainer: &[u8]) -> String {
+        format!("; trainer not supported by NESASM, ${:x} bytes dropped", trainer.len())
+    }
+
+    fn prg_bank(&self, bank: &PrgBank) -> String {
+        format!("  .bank {}\n  .org ${:04x}", bank.index, bank.address)
+    }
+
+    fn chr(&self, rom: &NesRom, path: &str) -> String {
+        let bank = rom.prg_rom().len() / NESASM_BANK_SIZE;
+
+        format!("  .bank {}\n  .org $0000\n  .incbin \"{}\"", bank, path)
+    }
+
+    fn extra(&self, bytes: &[u8]) -> String {
+        format!("; not supported by NESASM, ${:x} bytes dropped", bytes.len())
+    }
+
+    fn bank_size(&self) -> Option<usize> {
+        Some(NESASM_BANK_SIZE)
+    }
+
+    fn indent(&self) -> &'static str {
+        "  "
+    }
+
+    fn data(&self, bytes: &[u8]) -> String {
+        format!(".db {}", byte_list(bytes))
+    }
+
+    fn word(&self, value: &str) -> String {
+        format!(".dw {}", value)
+    }
+
+    fn word_big_endian(&self, value: &str) -> String {
+        format!(".db HIGH({0}), LOW({0})", value)
+    }
+
+    /// Indirect addresses use brackets, `<` selects the zero page
+    fn operand(&self, mode: AddressingMode, arg: &str, absolute: bool) -> Option<String> {
+        if absolute {
+            return None;
+        }
+
+        let ret = match mode {
+            AddressingMode::Indirect
+            | AddressingMode::IndexedIndirect
+            | AddressingMode::IndirectIndexed => arg
+                .replace('(', "[")
+                .replace(')', "]"),
+            AddressingMode::ZeroPage
+            | AddressingMode::ZeroPageX
+            | AddressingMode::ZeroPageY => format!("<{}", arg),
+            _ => arg.to_string()
+        };
+
+        Some(ret)
+    }
+}
+
+/// 64tass, assembled as a flat binary (`--nostart`)
+pub struct Tass64;
+
+impl Dialect for Tass64 {
+    fn name(&self) -> &'static str {
+        "64tass"
+    }
+
+    fn unofficial(&self) -> bool {
+        true
+    }
+
+    fn prologue(&self) -> String {
+        String::from("  .cpu \"6502i\"")
+    }
+
+    fn header(&self, rom: &NesRom) -> String {
+        format!("* = $0000\n{}{}", self.indent(), self.data(rom.header().bytes()))
+    }
+
+    fn prg_bank(&self, bank: &PrgBank) -> String {
+        format!("  .logical ${:04x}", bank.address)
+    }
+
+    fn prg_bank_end(&self, _bank: &PrgBank) -> String {
+        String::from("  .here")
+    }
+
+    fn chr(&self, _rom: &NesRom, path: &str) -> String {
+        format!("  .binary \"{}\"", path)
+    }
+
+    fn label(&self, name: &str) -> String {
+        name.to_string()
+    }
+
+    fn indent(&self) -> &'static str {
+        "  "
+    }
+
+    fn operand(&self, _mode: AddressingMode, arg: &str, absolute: bool) -> Option<String> {
+        if absolute {
+            return Some(format!("@w {}", arg));
+        }
+
+        Some(arg.to_string())
+    }
+}
@@ -1,247 +1,1555 @@
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet
+};
+
 use crate::{
     disassembler::{
-        header::NesHeader,
-        line::Line
+        bank::{
+            is_ambiguous,
+            prg_banks,
+            resolve_bank,
+            PrgBank
+        },
+        cdl::{
+            Cdl,
+            CdlUsage
+        },
+        dialect::{
+            Asm6,
+            Dialect
+        },
+        graph::Graph,
+        label::{
+            LabelKind,
+            Labels
+        },
+        line::Line,
+        symbol::{
+            Symbol,
+            SymbolLocation,
+            Symbols
+        },
+        table::{
+            find_tables,
+            JumpTable,
+            TableEntry
+        },
+        trace::{
+            vectors,
+            ByteKind,
+            EntryPoint,
+            Tracer
+        },
+        xref::{
+            Xref,
+            XrefKind,
+            Xrefs
+        }
     },
+    rom::rom::NesRom,
     utils::{
+        addressing::AddressingMode,
         error::NesError,
-        block::Block,
-        opcode::get_nes_opcode,
+        opcode::{
+            find_opcode,
+            get_nes_opcode,
+            get_official_opcode,
+            OpCodeSet
+        },
+        registers::register_bits,
         util::{
             path_to_name,
             join_bytes,
             create_and_write_file,
         }
     },
-    models::{
-        header_model::Header,
-        nesutil_model::{
-            Util,
-            Save, NesUtil
-        }
+    models::nesutil_model::{
+        Util,
+        Save, NesUtil
     }
 };
 
 /// Representing a constant value (in the assembly code)
 pub type EquConst = (u16, String);
 
+/// Maximum number of bytes on a data line
+const DATA_LINE_SIZE: usize = 8;
+
+/// Maximum number of references on a comment line
+const XREF_LINE_SIZE: usize = 6;
+
+/// How the PRG ROM bytes are split between code and data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisassemblyMode {
+    /// Decode every byte as code
+    Linear,
+    /// Decode the bytes reached from the CPU vectors and the entry points,
+    /// the other ones are data
+    #[default]
+    Tracing
+}
+
 /// Interacting with NES header, PRG ROM and CHR ROM.
 ///
 /// # Examples
 ///
 /// Basic usage:
 ///
-/// ```
+/// ```no_run
 /// use nes_utils::disassembler::disassembler::NesDisassembler;
 /// use nes_utils::models::nesutil_model::Util;
-/// 
-/// use std::fs::File;
-/// use std::io::Read;
-/// 
-/// let path = String::from("games/game.nes");
-/// let mut mem = Vec::<u8>::new();
-/// let mut f = File::open(&path).unwrap();
+/// use nes_utils::rom::rom::NesRom;
 ///
-/// f.read_to_end(&mut mem);
+/// let rom = NesRom::from_file("games/game.nes").unwrap();
 ///
-/// let mut d = NesDisassembler::new(&path, &mem);
-/// d.run();
+/// let mut d = NesDisassembler::new(&rom);
+/// d.run().unwrap();
 /// d.dump();
 /// ```
-pub struct NesDisassembler {
-    path: String,
-    header: NesHeader,
-    mem: Vec<u8>,
+pub struct NesDisassembler<'a> {
+    rom: &'a NesRom,
+    mode: DisassemblyMode,
+    opcodes: OpCodeSet,
+    dialect: Box<dyn Dialect>,
+    entry_points: Vec<EntryPoint>,
+    cdl: Option<Cdl>,
+    find_jump_tables: bool,
+    jump_tables: Vec<JumpTable>,
     const_lines: Vec<EquConst>,
-    prg_lines: Vec<Line>,
-    pc: usize,
-    prg_rom: Block,
-    chr_rom: Block
+    labels: Labels,
+    xrefs: Xrefs,
+    xref_comments: bool,
+    symbols: Symbols,
+    /// Imported symbols outside of the PRG ROM, written as constants
+    ram_symbols: BTreeMap<u16, Symbol>,
+    banks: Vec<(PrgBank, Vec<Line>)>
 }
 
-impl NesDisassembler {
-    pub fn new(path: &String, mem: &Vec<u8>) -> Self {
-
+impl<'a> NesDisassembler<'a> {
+    pub fn new(rom: &'a NesRom) -> Self {
         Self {
-            path: String::from(path),
-            header: NesHeader::new(&mem),
-            mem: mem.to_vec(),
+            rom,
+            mode: DisassemblyMode::default(),
+            opcodes: OpCodeSet::default(),
+            dialect: Box::new(Asm6::default()),
+            entry_points: Vec::new(),
+            cdl: None,
+            find_jump_tables: true,
+            jump_tables: Vec::new(),
             const_lines: Vec::new(),
-            prg_lines: Vec::new(),
-            pc: 0,
-            prg_rom: Block::new(NesHeader::HEADER_SIZE, 0),
-            chr_rom: Block::new(0, 0)
+            labels: Labels::new(),
+            xrefs: Xrefs::new(),
+            xref_comments: false,
+            symbols: Symbols::new(),
+            ram_symbols: BTreeMap::new(),
+            banks: Vec::new()
         }
     }
 
-    fn parse(&mut self) -> &mut Self {
-        self.header.parse();
-        
-        // Header size
-        self.pc = NesHeader::HEADER_SIZE;
+    pub fn set_mode(&mut self, mode: DisassemblyMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
 
-        // Get header metadata
-        self.prg_rom.size = self.header.field("len_prg_rom").value.unwrap()[0] as usize * NesHeader::PRG_ROM_UNIT_SIZE;
-        self.chr_rom.size = self.header.field("len_chr_rom").value.unwrap()[0] as usize * NesHeader::CHR_ROM_UNIT_SIZE;
+    /// Decode the unofficial opcodes with `OpCodeSet::Unofficial`,
+    /// they are data bytes by default
+    pub fn set_opcodes(&mut self, opcodes: OpCodeSet) -> &mut Self {
+        self.opcodes = opcodes;
+        self
+    }
 
-        // Check if there is the trainer (512 bytes)
-        if self.header.is_trainer() {
-            self.prg_rom.pos += NesHeader::TRAINER_SIZE;
-            self.pc = self.prg_rom.pos;
-        }
-        self.chr_rom.pos = self.prg_rom.size + self.prg_rom.pos;
+    /// Assembler syntax of the output, asm6 by default
+    pub fn set_dialect<D: Dialect + 'static>(&mut self, dialect: D) -> &mut Self {
+        self.dialect = Box::new(dialect);
+        self
+    }
+
+    /// Trace code from `address` too, `bank` is needed if the address
+    /// is in a switchable bank
+    pub fn add_entry_point(&mut self, address: u16, bank: Option<usize>) -> &mut Self {
+        self.entry_points.push(EntryPoint { address, bank });
+        self
+    }
+
+    /// Follow the jump tables of the `rts` trick and of `jmp (ptr)` while
+    /// tracing, enabled by default. The entries of the tables of words
+    /// are written `.word label-1` and `.word label`, or as byte pairs
+    /// `>(label-1), <(label-1)` if stored high byte first.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use nes_utils::disassembler::disassembler::NesDisassembler;
+    /// use nes_utils::models::nesutil_model::Util;
+    /// use nes_utils::rom::rom::NesRom;
+    ///
+    /// // NROM-128, reset: lda $c00a,y; pha; lda $c009,y; pha; rts
+    /// // then a table of one word, $c00b - 1
+    /// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00];
+    /// mem.resize(16, 0x00);
+    /// mem.resize(16 + 0x4000, 0xff);
+    /// mem[16..30].copy_from_slice(&[
+    ///     0xb9, 0x0a, 0xc0, 0x48, 0xb9, 0x09, 0xc0, 0x48, 0x60,
+    ///     0x0a, 0xc0,
+    ///     0x4c, 0x0b, 0xc0
+    /// ]);
+    /// mem[16 + 0x3ffa..].copy_from_slice(&[0x00, 0xc0, 0x00, 0xc0, 0x00, 0xc0]);
+    ///
+    /// let rom = NesRom::new("game.nes", &mem).unwrap();
+    ///
+    /// let mut d = NesDisassembler::new(&rom);
+    /// d.run().unwrap();
+    ///
+    /// assert_eq!(d.jump_tables()[0].targets, vec![0xc00b]);
+    /// assert!(d.labels().iter().any(|label| label.address == 0xc00b));
+    /// ```
+    pub fn set_jump_tables(&mut self, enabled: bool) -> &mut Self {
+        self.find_jump_tables = enabled;
+        self
+    }
+
+    /// Jump tables found while tracing
+    pub fn jump_tables(&self) -> &[JumpTable] {
+        &self.jump_tables
+    }
 
-        // Fill the blocks
-        self.prg_rom.value_from(&self.mem);
-        self.chr_rom.value_from(&self.mem);
+    /// Classify the PRG ROM bytes with the Code/Data Logger file of an emulator:
+    /// the logged code is traced, the logged data is never decoded and the
+    /// unused, indirect and DMC bytes are commented.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// use nes_utils::disassembler::cdl::Cdl;
+    /// use nes_utils::disassembler::disassembler::NesDisassembler;
+    /// use nes_utils::models::nesutil_model::{Util, Save};
+    /// use nes_utils::rom::rom::NesRom;
+    ///
+    /// let rom = NesRom::from_file("games/game.nes").unwrap();
+    /// let cdl = Cdl::from_file(&rom, "games/game.cdl").unwrap();
+    ///
+    /// let mut d = NesDisassembler::new(&rom);
+    /// d.set_cdl(cdl);
+    /// d.run().unwrap();
+    /// d.save().unwrap();
+    /// ```
+    pub fn set_cdl(&mut self, cdl: Cdl) -> &mut Self {
+        self.cdl = Some(cdl);
+        self
+    }
 
+    /// Name the addresses (PRG banks, RAM and registers) with the symbols
+    /// of a symbol file, their comments are written in the output
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// use nes_utils::disassembler::disassembler::NesDisassembler;
+    /// use nes_utils::disassembler::symbol::Symbols;
+    /// use nes_utils::models::nesutil_model::{Util, Save};
+    /// use nes_utils::rom::rom::NesRom;
+    ///
+    /// let rom = NesRom::from_file("games/game.nes").unwrap();
+    ///
+    /// let mut d = NesDisassembler::new(&rom);
+    /// d.add_symbols(Symbols::from_file("games/game.nes.ram.nl").unwrap());
+    /// d.add_symbols(Symbols::from_file("games/game.mlb").unwrap());
+    /// d.run().unwrap();
+    /// d.save().unwrap();
+    /// ```
+    pub fn add_symbols(&mut self, symbols: Symbols) -> &mut Self {
+        self.symbols.extend(symbols);
         self
     }
 
+    /// References to the addresses from the instructions
+    pub fn xrefs(&self) -> &Xrefs {
+        &self.xrefs
+    }
+
+    /// Write the references to the labeled lines above them
+    pub fn set_xref_comments(&mut self, enabled: bool) -> &mut Self {
+        self.xref_comments = enabled;
+        self
+    }
+
+    /// Names of the output (labels, imported symbols and registers)
+    pub fn symbols(&self) -> Symbols {
+        let mut ret = Symbols::new();
+
+        for (bank, lines) in &self.banks {
+            for line in lines {
+                let offset = bank.offset_of(line.address);
+
+                if let (Some(label), Some(offset)) = (&line.label, offset) {
+                    ret.push(SymbolLocation::Prg(offset), label, line.symbol_comment.as_deref());
+                }
+            }
+        }
+
+        for (address, symbol) in &self.ram_symbols {
+            ret.push(SymbolLocation::Cpu(*address), &symbol.name, symbol.comment.as_deref());
+        }
+
+        for (address, name) in &self.const_lines {
+            ret.push(SymbolLocation::Cpu(*address), name, None);
+        }
+
+        ret
+    }
+
+    /// Write the symbols for the debuggers next to the ROM file:
+    /// the FCEUX `.nl` files (`game.nes.0.nl`, `game.nes.ram.nl`),
+    /// the Mesen `game.mlb` and `game.json`.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// use nes_utils::disassembler::disassembler::NesDisassembler;
+    /// use nes_utils::models::nesutil_model::Util;
+    /// use nes_utils::rom::rom::NesRom;
+    ///
+    /// let rom = NesRom::from_file("games/game.nes").unwrap();
+    ///
+    /// let mut d = NesDisassembler::new(&rom);
+    /// d.run().unwrap();
+    /// d.save_symbols("games/game.nes").unwrap();
+    /// ```
+    pub fn save_symbols(&self, rom_path: &str) -> Result<(), NesError> {
+        let symbols = self.symbols();
+        let banks = self.prg_banks();
+        let stem = match rom_path.rsplit_once('.') {
+            Some((stem, _)) if !stem.is_empty() => stem,
+            _ => rom_path
+        };
+
+        for bank in symbols.nl_banks() {
+            let path = format!("{}.{:X}.nl", rom_path, bank);
+
+            create_and_write_file(&path, symbols.to_nl(&banks, Some(bank)).as_bytes())?;
+        }
+
+        let ram = symbols.to_nl(&banks, None);
+
+        if !ram.is_empty() {
+            create_and_write_file(&format!("{}.ram.nl", rom_path), ram.as_bytes())?;
+        }
+
+        create_and_write_file(&format!("{}.mlb", stem), symbols.to_mlb().as_bytes())?;
+        create_and_write_file(&format!("{}.json", stem), symbols.to_json(&banks).as_bytes())
+    }
+
+    /// Call graph of the routines and control flow graph of each one,
+    /// built from the vectors, the entry points, the jump table targets
+    /// and the `jsr` targets
+    pub fn graph(&self) -> Graph {
+        let banks = self.prg_banks();
+        let from = banks.last().map(|bank| bank.index);
+
+        let entries: Vec<(usize, u16)> = vectors(self.rom.prg_rom())
+            .into_iter()
+            .map(|(_, address)| EntryPoint { address, bank: from })
+            .chain(self.entry_points.iter().copied())
+            .chain(self.jump_tables.iter().flat_map(|table| {
+                table.targets.iter().map(|address| EntryPoint {
+                    address: *address,
+                    bank: Some(table.bank)
+                })
+            }))
+            .filter_map(|entry| {
+                resolve_bank(&banks, entry.bank, entry.address)
+                    .map(|bank| (bank.index, entry.address))
+            })
+            .collect();
+
+        Graph::new(&self.banks, &entries, &|line| fmt_line(self.dialect.as_ref(), line))
+    }
+
+    /// Write the graphs with the prefix `name`: the call graph
+    /// `name.calls.dot`, a control flow graph `name.<routine>.dot`
+    /// for each routine and all of them in `name.graph.json`.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// use nes_utils::disassembler::disassembler::NesDisassembler;
+    /// use nes_utils::models::nesutil_model::Util;
+    /// use nes_utils::rom::rom::NesRom;
+    ///
+    /// let rom = NesRom::from_file("games/game.nes").unwrap();
+    ///
+    /// let mut d = NesDisassembler::new(&rom);
+    /// d.run().unwrap();
+    /// d.save_graphs("graphs/game").unwrap();
+    /// ```
+    pub fn save_graphs(&self, name: &str) -> Result<(), NesError> {
+        let graph = self.graph();
+
+        create_and_write_file(&format!("{}.calls.dot", name), graph.call_graph_dot().as_bytes())?;
+
+        for routine in graph.routines() {
+            create_and_write_file(
+                &format!("{}.{}.dot", name, routine.name),
+                graph.cfg_dot(routine).as_bytes()
+            )?;
+        }
+
+        create_and_write_file(&format!("{}.graph.json", name), graph.to_json().as_bytes())
+    }
+
+    /// Parts of the ROM the dialect cannot write (NESASM header bytes,
+    /// trainer, misc ROM), the saved source does not assemble to the ROM
+    /// if there is any
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use nes_utils::disassembler::dialect::Nesasm;
+    /// use nes_utils::disassembler::disassembler::NesDisassembler;
+    /// use nes_utils::rom::rom::NesRom;
+    ///
+    /// // NROM-128 with a trainer
+    /// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00, 0x04];
+    /// mem.resize(16 + 512 + 0x4000, 0x00);
+    ///
+    /// let rom = NesRom::new("game.nes", &mem).unwrap();
+    ///
+    /// let mut d = NesDisassembler::new(&rom);
+    /// assert!(d.unsupported().is_empty());
+    ///
+    /// d.set_dialect(Nesasm);
+    /// assert_eq!(d.unsupported(), vec!["trainer"]);
+    /// ```
+    pub fn unsupported(&self) -> Vec<&'static str> {
+        self.dialect.unsupported(self.rom)
+    }
+
+    /// Disassembled ROM
+    pub fn rom(&self) -> &'a NesRom {
+        self.rom
+    }
+
+    /// Labels set on the disassembled lines
+    pub fn labels(&self) -> &Labels {
+        &self.labels
+    }
+
+    fn parse(&mut self) -> Result<&mut Self, NesError> {
+        let expected = self.rom.header().prg_rom_size();
+        let actual = self.rom.prg_rom().len();
+
+        if actual < expected {
+            return Err(NesError::UnexpectedSize {
+                offset: self.rom.prg_rom_offset(),
+                expected,
+                actual
+            });
+        }
+
+        Ok(self)
+    }
+
+    /// PRG banks of the disassembled lines
+    fn prg_banks(&self) -> Vec<PrgBank> {
+        self.banks
+            .iter()
+            .map(|(bank, _)| *bank)
+            .collect()
+    }
+
+    fn trace(&mut self, banks: &[PrgBank]) -> Option<Vec<ByteKind>> {
+        self.jump_tables = Vec::new();
+
+        if self.mode == DisassemblyMode::Linear {
+            return None;
+        }
+
+        let prg = self.rom.prg_rom();
+        let mut tracer = Tracer::new(prg, banks);
+
+        tracer.set_opcodes(self.opcodes);
+        if let Some(cdl) = &self.cdl {
+            trace_cdl(&mut tracer, banks, cdl);
+        }
+        tracer.trace_vectors();
+        for entry in &self.entry_points {
+            tracer.trace(*entry);
+        }
+
+        // The table targets may dispatch through other tables
+        let mut traced = HashSet::new();
+
+        while self.find_jump_tables {
+            self.jump_tables = find_tables(prg, banks, tracer.kinds(), self.opcodes);
+
+            let entries: Vec<EntryPoint> = self.jump_tables
+                .iter()
+                .flat_map(|table| table.targets.iter().map(|address| EntryPoint {
+                    address: *address,
+                    bank: Some(table.bank)
+                }))
+                .filter(|entry| traced.insert((entry.bank, entry.address)))
+                .collect();
+
+            if entries.is_empty() {
+                break;
+            }
+
+            for entry in entries {
+                tracer.trace(entry);
+            }
+        }
+
+        Some(tracer.kinds().to_vec())
+    }
+
     fn disassemble(&mut self) -> &mut Self {
-        // Header parsing + link metadata
-        self.parse();
-        self.prg_lines = Vec::new();
-
-        // Disassemble
-        while self.pc < self.prg_rom.size {
-            // Check if the opcode has been implemented
-            let byte = self.mem[self.pc];
-            let code = match get_nes_opcode(&byte) {
-                Some(value) => value,
-                None => panic!(
-                    "{} (0x{:02x?})",
-                    NesError::NotImplementedOpcode, byte
-                )
+        let banks = prg_banks(self.rom);
+        let kinds = self.trace(&banks);
+
+        // Entries of the tables of words, by PRG ROM offset
+        let mut entries = HashMap::new();
+
+        for table in &self.jump_tables {
+            let format = match table.entry_format() {
+                Some(format) => format,
+                None => continue
             };
 
-            let range = self.pc..self.pc + (code.len as usize);
-            let code_bytes = &mut self.mem[range];
+            for n in 0..table.targets.len() {
+                let address = table.entry(n);
+                let offset = resolve_bank(&banks, Some(table.bank), address)
+                    .and_then(|bank| bank.offset_of(address));
 
-            let mut line = Line {
-                bytes: code_bytes.to_vec(),
-                opcode: code,
-                label: None,
-                fmt_arg: String::from(""),
-                comment: None
+                if let Some(offset) = offset {
+                    entries.insert(offset, format);
+                }
+            }
+        }
+
+        self.banks = Vec::new();
+
+        for bank in &banks {
+            let lines = match &kinds {
+                Some(kinds) => self.disassemble_traced_bank(bank, kinds, &entries),
+                None => self.disassemble_bank(bank)
             };
-            if let Some(equ) = line.fmt() {
-                if self.const_lines.contains(&equ) == false {
-                    self.const_lines.push(equ);
+
+            self.banks.push((*bank, lines));
+        }
+
+        self
+    }
+
+    /// Decode every byte of the bank, `offset` is relative to the PRG ROM
+    fn disassemble_bank(&mut self, bank: &PrgBank) -> Vec<Line> {
+        let prg = self.rom.prg_rom();
+        let end = bank.offset + bank.size;
+        let mut lines = Vec::new();
+        let mut offset = bank.offset;
+
+        while offset < end {
+            let address = bank.address_of(offset);
+
+            let byte = prg[offset];
+            let mut line = match self.opcodes.decode(&byte) {
+                None => {
+                    let note = match get_nes_opcode(&byte) {
+                        Some(code) => format!("unofficial {}", code.mnemonic),
+                        None => String::from("unknown opcode")
+                    };
+
+                    Line::undecodable(address, &prg[offset..offset + 1], &note)
+                },
+                // Instruction straddling the end of the bank
+                Some(code) if offset + code.len as usize > end => {
+                    let note = format!("truncated {}", code.mnemonic);
+
+                    Line::undecodable(address, &prg[offset..end], &note)
+                },
+                Some(code) => {
+                    Line::instruction(address, &prg[offset..offset + code.len as usize], code)
+                }
+            };
+
+            self.fmt_line(&mut line);
+
+            offset += line.bytes.len();
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    fn disassemble_traced_bank(
+        &mut self,
+        bank: &PrgBank,
+        kinds: &[ByteKind],
+        entries: &HashMap<usize, TableEntry>
+    ) -> Vec<Line> {
+        let prg = self.rom.prg_rom();
+        let end = bank.offset + bank.size;
+        let mut lines = Vec::new();
+        let mut offset = bank.offset;
+
+        while offset < end {
+            let address = bank.address_of(offset);
+            let mut line = match self.opcodes.decode(&prg[offset]) {
+                Some(code) if kinds[offset] == ByteKind::Code => {
+                    Line::instruction(address, &prg[offset..offset + code.len as usize], code)
+                },
+                _ if entries.contains_key(&offset) && offset + 2 <= end => {
+                    Line::table_entry(address, &prg[offset..offset + 2], entries[&offset])
+                },
+                _ => {
+                    // Data run, up to the next instruction or logged usage
+                    let usage = self.cdl_usage(offset);
+                    let size = kinds[offset + 1..end]
+                        .iter()
+                        .zip(offset + 1..)
+                        .take(DATA_LINE_SIZE - 1)
+                        .take_while(|(kind, next)| {
+                            **kind != ByteKind::Code
+                                && !entries.contains_key(next)
+                                && self.cdl_usage(*next) == usage
+                        })
+                        .count() + 1;
+
+                    Line::data(address, &prg[offset..offset + size])
                 }
+            };
+
+            self.fmt_line(&mut line);
+
+            offset += line.bytes.len();
+            lines.push(line);
+        }
+
+        lines
+    }
+
+    /// Logged usage of a PRG ROM byte
+    fn cdl_usage(&self, offset: usize) -> Option<CdlUsage> {
+        self.cdl.as_ref().map(|cdl| cdl.usage(offset))
+    }
+
+    /// Format the operand and keep the mapped register it uses
+    fn fmt_line(&mut self, line: &mut Line) {
+        if let Some(equ) = line.fmt() {
+            if !self.const_lines.contains(&equ) {
+                self.const_lines.push(equ);
             }
+        }
+    }
 
-            // Fill self.prg_lines
-            self.prg_lines.push(line);
+    fn add_labels(&mut self) -> &mut Self {
+        let banks = self.prg_banks();
+        let mut labels = Labels::new();
 
-            // Next line
-            self.pc += code.len as usize;
+        // Vectors are read from the last bank
+        let from = banks.last().map(|bank| bank.index);
+
+        for (name, address) in vectors(self.rom.prg_rom()) {
+            labels.insert(&banks, from, address, LabelKind::Vector(name));
         }
 
+        for (bank, lines) in &self.banks {
+            for (address, kind) in lines.iter().filter_map(Line::reference) {
+                labels.insert(&banks, Some(bank.index), address, kind);
+            }
+        }
+
+        for table in &self.jump_tables {
+            for address in &table.targets {
+                labels.insert(&banks, Some(table.bank), *address, LabelKind::Location);
+            }
+        }
+
+        // Imported symbols, with their comments
+        let mut comments = HashMap::new();
+        let mut names: HashSet<String> = labels
+            .iter()
+            .map(|label| label.name.clone())
+            .chain(self.const_lines.iter().map(|(_, name)| name.clone()))
+            .collect();
+
+        for symbol in self.symbols.iter() {
+            let (bank, address) = match symbol.prg_address(&banks) {
+                Some(location) => location,
+                None => continue
+            };
+
+            // The first symbol of an address wins
+            if labels.get(&banks, Some(bank.index), address)
+                .is_some_and(|label| label.kind == LabelKind::Symbol)
+            {
+                continue;
+            }
+
+            let name = unique_name(&mut names, &symbol.name);
+
+            labels.insert_symbol(bank, address, &name);
+            if let Some(comment) = &symbol.comment {
+                comments.insert((bank.index, address), comment.clone());
+            }
+        }
+
+        // Data lines are split to start on the labels
+        for (bank, lines) in &mut self.banks {
+            let addresses: Vec<u16> = labels
+                .bank_addresses(bank.index)
+                .collect();
+
+            *lines = lines
+                .drain(..)
+                .flat_map(|line| split_line(line, &addresses))
+                .collect();
+        }
+
+        // Labels in the middle of an instruction or a table entry are dropped
+        labels.retain(|label| {
+            self.banks
+                .iter()
+                .filter(|(bank, _)| bank.index == label.bank)
+                .flat_map(|(_, lines)| lines)
+                .any(|line| line.address == label.address)
+        });
+
+        let entries: HashSet<(usize, u16)> = self.banks
+            .iter()
+            .flat_map(|(bank, lines)| lines
+                .iter()
+                .filter(|line| line.table.is_some())
+                .map(|line| (bank.index, line.address)))
+            .collect();
+
+        for (bank, lines) in &mut self.banks {
+            for line in lines.iter_mut() {
+                if let Some(label) = labels.get(&banks, Some(bank.index), line.address) {
+                    if label.bank == bank.index {
+                        line.label = Some(label.name.clone());
+                    }
+                }
+
+                line.symbol_comment = comments.get(&(bank.index, line.address)).cloned();
+
+                let name = line.reference().and_then(|(address, _)| {
+                    match labels.get(&banks, Some(bank.index), address) {
+                        Some(label) => Some(label.name.clone()),
+                        // High byte of a table entry
+                        None => labels
+                            .get(&banks, Some(bank.index), address.wrapping_sub(1))
+                            .filter(|label| entries.contains(&(label.bank, label.address)))
+                            .map(|label| format!("{}+1", label.name))
+                    }
+                });
+
+                if let Some(name) = name {
+                    line.set_arg_label(&name);
+                }
+            }
+        }
+
+        self.labels = labels;
         self
     }
 
-    fn add_comments(&mut self) -> &mut Self {
-        let mut comment;
-        let n = self.prg_lines
+    /// Name the operands outside of the PRG ROM with the imported symbols
+    fn add_ram_symbols(&mut self) -> &mut Self {
+        let banks = self.prg_banks();
+        let mut names: HashSet<String> = self.labels
             .iter()
-            .max_by(|x, y| x.cmp(y));
-        
-        let n = match n {
-            Some(line) => line.len(),
-            None => 0
-        };
+            .map(|label| label.name.clone())
+            .collect();
+        let mut symbols: BTreeMap<u16, Symbol> = BTreeMap::new();
 
-        let mut spaces;
+        for symbol in self.symbols.iter() {
+            if let SymbolLocation::Cpu(address) = symbol.location {
+                if resolve_bank(&banks, None, address).is_none() && !symbols.contains_key(&address) {
+                    let mut symbol = symbol.clone();
 
-        for line in self.prg_lines.iter_mut() {
-            spaces = " ".repeat(n - line.len());
-            comment = join_bytes(&line.bytes, " ");
-            comment = format!("{} ; {}", spaces, comment);
-            line.comment = Some(comment);
+                    symbol.name = unique_name(&mut names, &symbol.name);
+                    symbols.insert(address, symbol);
+                }
+            }
         }
 
+        let lines = self.banks
+            .iter_mut()
+            .flat_map(|(_, lines)| lines.iter_mut());
+
+        for line in lines {
+            let symbol = line
+                .operand_address()
+                .filter(|address| resolve_bank(&banks, None, *address).is_none())
+                .and_then(|address| symbols.get(&address));
+
+            if let Some(symbol) = symbol {
+                line.set_arg_label(&symbol.name);
+            }
+        }
+
+        // Registers renamed by a symbol
+        self.const_lines.retain(|(address, _)| !symbols.contains_key(address));
+
+        self.ram_symbols = symbols;
         self
     }
 
-    fn dump_chr(&mut self, path: &str) -> bool {
-        match &self.chr_rom.value {
-            Some(data) => {
-                create_and_write_file(path, &data);
-                true
-            },
-            None => false
+    fn add_xrefs(&mut self) -> &mut Self {
+        let banks = self.prg_banks();
+        let mut xrefs = Xrefs::new();
+
+        for (bank, lines) in &self.banks {
+            for line in lines {
+                let kind = match line.opcode.as_ref().and_then(XrefKind::of) {
+                    Some(kind) => kind,
+                    None => continue
+                };
+                let address = match line.target().or_else(|| line.operand_address()) {
+                    Some(address) => address,
+                    None => continue
+                };
+                let target = resolve_bank(&banks, Some(bank.index), address)
+                    .map(|bank| bank.index);
+
+                xrefs.insert(target, address, Xref {
+                    bank: bank.index,
+                    address: line.address,
+                    kind
+                });
+            }
+        }
+
+        if self.xref_comments {
+            for (bank, lines) in &mut self.banks {
+                for line in lines.iter_mut().filter(|line| line.label.is_some()) {
+                    let refs: Vec<String> = xrefs
+                        .get(Some(bank.index), line.address)
+                        .iter()
+                        .map(|xref| match is_ambiguous(&banks, xref.address) {
+                            true => format!("{} ${:02x}:{:04x}", xref.kind.name(), xref.bank, xref.address),
+                            false => format!("{} ${:04x}", xref.kind.name(), xref.address)
+                        })
+                        .collect();
+
+                    if !refs.is_empty() {
+                        let comment: Vec<String> = refs
+                            .chunks(XREF_LINE_SIZE)
+                            .map(|refs| format!("xref: {}", refs.join(", ")))
+                            .collect();
+
+                        line.xref_comment = Some(comment.join("\n"));
+                    }
+                }
+            }
+        }
+
+        self.xrefs = xrefs;
+        self
+    }
+
+    fn add_comments(&mut self) -> &mut Self {
+        let cdl = self.cdl.as_ref();
+
+        for (bank, lines) in &mut self.banks {
+            let mut immediates = Immediates::default();
+
+            for line in lines.iter_mut() {
+                let mut comment = format!(
+                    "; ${:04x}: {}",
+                    line.address,
+                    join_bytes(&line.bytes, " ")
+                );
+                let mut notes: Vec<&str> = line.note.iter().map(String::as_str).collect();
+
+                // Bits of an immediate value stored to a register
+                let bits = immediates
+                    .step(line)
+                    .and_then(|(address, value)| register_bits(address, value));
+
+                notes.extend(bits.as_deref());
+
+                // Logged usage shared by the bytes of the line
+                let usage = cdl.zip(bank.offset_of(line.address)).and_then(|(cdl, offset)| {
+                    let usage = cdl.usage(offset);
+
+                    (offset..offset + line.bytes.len())
+                        .all(|offset| cdl.usage(offset) == usage)
+                        .then_some(usage)
+                });
+
+                if let Some(usage) = usage.filter(|usage| !usage.note().is_empty()) {
+                    notes.push(usage.note());
+                }
+
+                if !notes.is_empty() {
+                    comment = format!("{} ({})", comment, notes.join(", "));
+                }
+
+                line.comment = Some(comment);
+            }
+        }
+
+        self
+    }
+
+    fn dump_chr(&mut self, path: &str) -> Result<bool, NesError> {
+        let data = self.rom.chr_rom();
+
+        // Missing or truncated CHR ROM
+        if data.is_empty() || data.len() < self.rom.header().chr_rom_size() {
+            return Ok(false);
+        }
+
+        create_and_write_file(path, data)?;
+        Ok(true)
+    }
+
+    /// Banks and lines, split into the banks of the dialect if needed
+    fn dialect_banks(&self, dialect: &dyn Dialect) -> Vec<(PrgBank, Vec<Line>)> {
+        let size = match dialect.bank_size() {
+            Some(size) => size,
+            None => return self.banks.clone()
+        };
+        let mut ret: Vec<(PrgBank, Vec<Line>)> = Vec::new();
+
+        for (bank, lines) in &self.banks {
+            for start in (0..bank.size).step_by(size) {
+                let piece = PrgBank {
+                    index: (bank.offset + start) / size,
+                    offset: bank.offset + start,
+                    size: size.min(bank.size - start),
+                    address: bank.address_of(bank.offset + start),
+                    fixed: bank.fixed
+                };
+
+                ret.push((piece, Vec::new()));
+            }
+
+            for line in lines {
+                let offset = bank.offset + line.address.wrapping_sub(bank.address) as usize;
+                let end = (offset / size + 1) * size;
+
+                // Lines crossing a dialect bank are cut in two
+                let mut line = if offset + line.bytes.len() > end && line.opcode.is_some() {
+                    let mut ret = Line::undecodable(line.address, &line.bytes, "bank boundary");
+
+                    ret.label = line.label.clone();
+                    ret.comment = line.comment.clone();
+                    ret
+                } else {
+                    line.clone()
+                };
+                let rest = (offset + line.bytes.len() > end)
+                    .then(|| line.split_off(end - offset));
+
+                for line in std::iter::once(line).chain(rest) {
+                    let index = (bank.offset + line.address.wrapping_sub(bank.address) as usize) / size;
+
+                    if let Some((_, lines)) = ret.iter_mut().find(|(piece, _)| piece.index == index) {
+                        lines.push(line);
+                    }
+                }
+            }
         }
+
+        ret
     }
 
-    fn fmt_lines(&mut self) -> String {
+    fn fmt_lines(&self, chr_path: Option<&str>) -> String {
+        self.source(self.dialect.as_ref(), chr_path)
+    }
+
+    /// Source code in a dialect, including the CHR ROM from `chr_path`
+    pub(crate) fn source(&self, dialect: &dyn Dialect, chr_path: Option<&str>) -> String {
         let mut ret = String::from("");
-    
+
+        let prologue = dialect.prologue();
+
+        if !prologue.is_empty() {
+            ret.push_str(&format!("{}\n\n", prologue));
+        }
+
         ret.push_str("; Mapped registers\n\n");
         for (value, name) in &self.const_lines {
-            ret.push_str(&format!("{} equ ${:02x?}\n", name, value));
+            ret.push_str(&format!("{}\n", dialect.constant(name, *value)));
         }
-        
+
+        if !self.ram_symbols.is_empty() {
+            ret.push_str("\n; Symbols\n\n");
+        }
+        for (address, symbol) in &self.ram_symbols {
+            let constant = dialect.constant(&symbol.name, *address);
+
+            match &symbol.comment {
+                Some(comment) => ret.push_str(&format!(
+                    "{} ; {}\n", constant, comment.replace('\n', " ")
+                )),
+                None => ret.push_str(&format!("{}\n", constant))
+            }
+        }
+
         // Dumping header
         ret.push_str("\n; Header\n\n");
-        ret.push_str(&format!("{}\n", self.header));
+        ret.push_str(&format!("{}\n", dialect.header(self.rom)));
 
-        // Dumping PRG
-        ret.push_str("\n; PRG ROM\n\n");
-        
-        for line in &self.prg_lines {
-            ret.push_str(&format!("{}", line));
+        if let Some(trainer) = self.rom.trainer() {
+            ret.push_str(&format!("\n; Trainer\n\n{}\n", dialect.trainer(trainer)));
+        }
+
+        // Dumping PRG, bank by bank
+        ret.push_str("\n; PRG ROM\n");
+
+        let banks = self.dialect_banks(dialect);
+        let texts: Vec<Vec<String>> = banks
+            .iter()
+            .map(|(_, lines)| lines
+                .iter()
+                .map(|line| format!("{}{}", dialect.indent(), fmt_line(dialect, line)))
+                .collect())
+            .collect();
+        let width = texts
+            .iter()
+            .flatten()
+            .map(|text| text.len())
+            .max()
+            .unwrap_or(0);
+
+        for ((bank, lines), texts) in banks.iter().zip(texts) {
+            ret.push_str(&format!(
+                "\n; Bank {} (${:04x})\n\n{}\n\n",
+                bank.index, bank.address, dialect.prg_bank(bank)
+            ));
+
+            for (line, text) in lines.iter().zip(texts) {
+                let comments = line.symbol_comment
+                    .iter()
+                    .chain(&line.xref_comment)
+                    .flat_map(|comment| comment.lines());
+
+                for comment in comments {
+                    ret.push_str(&format!("; {}\n", comment));
+                }
+
+                if let Some(label) = &line.label {
+                    ret.push_str(&format!("{}\n", dialect.label(label)));
+                }
+
+                match &line.comment {
+                    Some(comment) => ret.push_str(&format!("{:width$} {}\n", text, comment)),
+                    None => ret.push_str(&format!("{}\n", text))
+                }
+            }
+
+            let end = dialect.prg_bank_end(bank);
+
+            if !end.is_empty() {
+                ret.push_str(&format!("{}\n", end));
+            }
+        }
+
+        if let Some(path) = chr_path {
+            ret.push_str(&format!("\n\n; CHR ROM\n{}\n", dialect.chr(self.rom, path)));
+        }
+
+        let extra = [
+            ("Misc ROM", self.rom.misc_rom()),
+            ("Trailing data", self.rom.trailing_data())
+        ];
+
+        for (name, bytes) in extra.iter().filter(|(_, bytes)| !bytes.is_empty()) {
+            ret.push_str(&format!("\n; {}\n\n{}\n", name, dialect.extra(bytes)));
         }
 
         ret
     }
 
     pub fn dump(&mut self) {
-        let content = self.fmt_lines();
+        let content = self.fmt_lines(None);
     
         println!("{}", content);
     }
 }
 
-impl NesUtil for NesDisassembler { }
+/// Line in the dialect syntax, instructions it cannot write are bytes
+fn fmt_line(dialect: &dyn Dialect, line: &Line) -> String {
+    let opcode = match &line.opcode {
+        Some(opcode) => opcode,
+        None => match line.table {
+            Some(entry) if entry.big_endian => return dialect.word_big_endian(&line.fmt_arg),
+            Some(_) => return dialect.word(&line.fmt_arg),
+            None if line.note.is_some() => return dialect.bytes(&line.bytes),
+            None => return dialect.data(&line.bytes)
+        }
+    };
+
+    // Unofficial opcodes must be the only encoding of the instruction
+    let encodable = get_official_opcode(&opcode.code).is_some()
+        || (dialect.unofficial()
+            && find_opcode(opcode.mnemonic, opcode.mode).map(|found| found.code) == Some(opcode.code));
+
+    // Zero page address with an absolute mode
+    let absolute = line.bytes.len() == 3
+        && line.bytes[2] == 0
+        && opcode.mode
+            .zero_page()
+            .and_then(|mode| find_opcode(opcode.mnemonic, mode))
+            .is_some();
+
+    let arg = match dialect.operand(opcode.mode, &line.fmt_arg, absolute) {
+        Some(arg) if encodable => arg,
+        _ => return dialect.bytes(&line.bytes)
+    };
+
+    if arg.is_empty() {
+        opcode.mnemonic.to_string()
+    } else {
+        format!("{} {}", opcode.mnemonic, arg)
+    }
+}
+
+/// Values of A, X and Y loaded with an immediate operand, known
+/// until the register is modified or another path joins on a label
+#[derive(Default)]
+struct Immediates {
+    a: Option<u8>,
+    x: Option<u8>,
+    y: Option<u8>
+}
+
+impl Immediates {
+    /// Follow a line, return the address and the value of an absolute
+    /// store of a known value
+    fn step(&mut self, line: &Line) -> Option<(u16, u8)> {
+        let opcode = match &line.opcode {
+            Some(opcode) if line.label.is_none() => opcode,
+            Some(opcode) => {
+                *self = Self::default();
+                opcode
+            },
+            None => {
+                *self = Self::default();
+                return None;
+            }
+        };
+        let immediate = line.bytes
+            .get(1)
+            .copied()
+            .filter(|_| opcode.mode == AddressingMode::Immediate);
+
+        let stored = match opcode.mnemonic {
+            "sta" => self.a,
+            "stx" => self.x,
+            "sty" => self.y,
+            _ => None
+        };
+
+        match opcode.mnemonic {
+            "lda" => self.a = immediate,
+            "ldx" => self.x = immediate,
+            "ldy" => self.y = immediate,
+            "tax" => self.x = self.a,
+            "tay" => self.y = self.a,
+            "txa" => self.a = self.x,
+            "tya" => self.a = self.y,
+            "inx" | "dex" => self.x = None,
+            "iny" | "dey" => self.y = None,
+            "sta" | "stx" | "sty" | "nop" | "bit" | "cmp" | "cpx" | "cpy" | "pha" | "php"
+            | "sei" | "cli" | "sec" | "clc" | "sed" | "cld" | "clv" => {},
+            _ if opcode.mode == AddressingMode::Relative => {},
+            _ => *self = Self::default()
+        };
+
+        let address = line
+            .operand_address()
+            .filter(|_| opcode.mode == AddressingMode::Absolute)?;
+
+        Some((address, stored?))
+    }
+}
+
+/// Trace the logged code, the logged data is never decoded
+fn trace_cdl(tracer: &mut Tracer, banks: &[PrgBank], cdl: &Cdl) {
+    for bank in banks {
+        let end = bank.offset + bank.size;
+
+        for offset in bank.offset..end {
+            if matches!(cdl.usage(offset), CdlUsage::Data | CdlUsage::IndirectData | CdlUsage::PcmData) {
+                tracer.set_data(offset);
+            }
+        }
+
+        for offset in bank.offset..end {
+            // Logged instructions start after non-code bytes or decoded instructions
+            let start = offset == bank.offset
+                || cdl.usage(offset - 1) != CdlUsage::Code
+                || tracer.kinds()[offset - 1] != ByteKind::Data;
+
+            if start && cdl.usage(offset) == CdlUsage::Code && tracer.kinds()[offset] == ByteKind::Data {
+                tracer.trace(EntryPoint {
+                    address: bank.address_of(offset),
+                    bank: Some(bank.index)
+                });
+            }
+        }
+    }
+}
+
+/// Name not used yet, a number is appended to the duplicates
+fn unique_name(names: &mut HashSet<String>, name: &str) -> String {
+    let mut ret = name.to_string();
+    let mut n = 1;
+
+    while names.contains(&ret) {
+        n += 1;
+        ret = format!("{}_{}", name, n);
+    }
 
-impl Util for NesDisassembler {
+    names.insert(ret.clone());
+    ret
+}
+
+/// Split a data line on the labeled addresses
+fn split_line(mut line: Line, addresses: &[u16]) -> Vec<Line> {
+    if line.opcode.is_some() || line.table.is_some() {
+        return vec![line];
+    }
+
+    let mut ret = Vec::new();
+    let start = line.address as usize;
+    let end = start + line.bytes.len();
+
+    let splits = addresses
+        .iter()
+        .map(|address| *address as usize)
+        .filter(|address| *address > start && *address < end);
+
+    for address in splits.rev() {
+        ret.push(line.split_off(address - line.address as usize));
+    }
+    ret.push(line);
+    ret.reverse();
+
+    ret
+}
+
+impl NesUtil for NesDisassembler<'_> { }
+
+impl Util for NesDisassembler<'_> {
     /// Parse the bytes and fill structs to format it later.
-    fn run(&mut self) {
+    fn run(&mut self) -> Result<(), NesError> {
         self
-            .parse()
+            .parse()?
             .disassemble()
+            .add_labels()
+            .add_ram_symbols()
+            .add_xrefs()
             .add_comments();
+
+        Ok(())
     }
 }
 
-impl Save for NesDisassembler {
+impl Save for NesDisassembler<'_> {
     /// Save the header and the PRG ROM (assembly code) to the path as argument.
     /// 
     /// Dump the CHR ROM data to a `.chr` file with the same prefix.
-    fn save_as(&mut self, path: &str) {
+    fn save_as(&mut self, path: &str) -> Result<(), NesError> {
         let name = path_to_name(path);
-        let mut line_str = self.fmt_lines();
-        
+
         // Dumping CHR
         let chr_path = format!("{}.chr", name);
+        let chr = self.dump_chr(&chr_path)?;
+        let line_str = self.fmt_lines(chr.then_some(chr_path.as_str()));
+
+        // Linker configuration
+        let banks = self.prg_banks();
 
-        if self.dump_chr(&chr_path) {
-            line_str.push_str(&format!("\n\n; CHR ROM\n.incbin {}.chr\n", name));
+        if let Some(config) = self.dialect.linker_config(self.rom, &banks) {
+            create_and_write_file(&format!("{}.cfg", name), config.as_bytes())?;
         }
 
         // Writing bytes to the file
         create_and_write_file(
-            &path.to_string(),
+            path,
             line_str.as_bytes()
-        );
+        )
     }
     
     /// Same as `save_as` but with the path stored in the struct.
-    fn save(&mut self) {
-        let name = path_to_name(&self.path);
+    fn save(&mut self) -> Result<(), NesError> {
+        let name = path_to_name(self.rom.path());
         let path = format!("./{}.asm", name);
         
         self.save_as(&path)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disassembler::dialect::Nesasm;
+
+    /// iNES file with a PRG ROM made of every byte value
+    fn rom(prg_banks: u8, flags_6: u8) -> NesRom {
+        let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, prg_banks, 0x00, flags_6];
+
+        mem.resize(16, 0x00);
+        if flags_6 & 0x04 != 0 {
+            mem.resize(16 + 512, 0xea);
+        }
+
+        let prg_size = prg_banks as usize * 0x4000;
+        mem.extend((0..prg_size).map(|n| (n * 7 % 256) as u8));
+
+        NesRom::new("game.nes", &mem).unwrap()
+    }
+
+    /// NROM-128 running `code` from $c000 on every vector
+    fn nrom_with_code(code: &[u8]) -> NesRom {
+        let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00];
+
+        mem.resize(16, 0x00);
+        mem.resize(16 + 0x4000, 0xff);
+        mem[16..16 + code.len()].copy_from_slice(code);
+        mem[16 + 0x3ffa..].copy_from_slice(&[0x00, 0xc0, 0x00, 0xc0, 0x00, 0xc0]);
+
+        NesRom::new("game.nes", &mem).unwrap()
+    }
+
+    /// Comment of the line at a CPU address
+    fn comment(d: &NesDisassembler, address: u16) -> String {
+        d.banks
+            .iter()
+            .flat_map(|(_, lines)| lines)
+            .find(|line| line.address == address)
+            .and_then(|line| line.comment.clone())
+            .unwrap()
+    }
+
+    /// Every PRG byte is on exactly one line, at its CPU address
+    fn assert_coverage(rom: &NesRom, mode: DisassemblyMode, opcodes: OpCodeSet) {
+        let mut d = NesDisassembler::new(rom);
+
+        d.set_mode(mode).set_opcodes(opcodes);
+        d.run().unwrap();
+
+        let mut covered: Vec<u8> = Vec::new();
+
+        for (bank, lines) in &d.banks {
+            let mut address = bank.address;
+
+            for line in lines {
+                assert_eq!(line.address, address, "bank {} in {:?}", bank.index, mode);
+                address = address.wrapping_add(line.bytes.len() as u16);
+                covered.extend(&line.bytes);
+            }
+        }
+
+        assert_eq!(covered, rom.prg_rom(), "{:?} {:?}", mode, opcodes);
+    }
+
+    #[test]
+    fn every_prg_byte_is_covered_once() {
+        let roms = [
+            // NROM-128, NROM-256 with a trainer, UxROM
+            rom(1, 0x00),
+            rom(2, 0x04),
+            rom(4, 0x20)
+        ];
+
+        for rom in &roms {
+            for mode in [DisassemblyMode::Linear, DisassemblyMode::Tracing] {
+                for opcodes in [OpCodeSet::Official, OpCodeSet::Unofficial] {
+                    assert_coverage(rom, mode, opcodes);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reassembly_is_byte_exact() {
+        let roms = [rom(1, 0x00), rom(2, 0x04), rom(4, 0x20)];
+
+        for rom in &roms {
+            for mode in [DisassemblyMode::Linear, DisassemblyMode::Tracing] {
+                for opcodes in [OpCodeSet::Official, OpCodeSet::Unofficial] {
+                    let mut d = NesDisassembler::new(rom);
+
+                    d.set_mode(mode).set_opcodes(opcodes);
+                    d.run().unwrap();
+
+                    let round_trip = d.asm6_round_trip().unwrap();
+
+                    assert!(
+                        round_trip.is_exact(),
+                        "{:?} {:?}: {:?}",
+                        mode,
+                        opcodes,
+                        &round_trip.differences[..round_trip.differences.len().min(4)]
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bytes_after_chr_are_reassembled() {
+        // Trailing data of an iNES file, misc ROM of a NES 2.0 file
+        let mut trailing = rom(1, 0x00).bytes().to_vec();
+        trailing.extend([0x12, 0x34, 0x56]);
+
+        let mut misc = trailing.clone();
+        misc[7] = 0x08;
+        misc[14] = 0x01;
+
+        for mem in [trailing, misc] {
+            let rom = NesRom::new("game.nes", &mem).unwrap();
+            let mut d = NesDisassembler::new(&rom);
+
+            d.run().unwrap();
+
+            assert_eq!(rom.misc_rom().len() + rom.trailing_data().len(), 3);
+            assert!(d.asm6_round_trip().unwrap().is_exact());
+        }
+    }
+
+    #[test]
+    fn cdl_data_is_never_decoded() {
+        let rom = rom(1, 0x00);
+        let mut log = vec![Cdl::DATA; rom.prg_rom().len()];
+
+        // Only the first instruction is logged as code
+        log[0] = Cdl::CODE;
+
+        let mut d = NesDisassembler::new(&rom);
+
+        d.set_cdl(Cdl::new(&rom, &log).unwrap());
+        d.run().unwrap();
+
+        let code: Vec<u16> = d.banks
+            .iter()
+            .flat_map(|(_, lines)| lines)
+            .filter(|line| line.opcode.is_some())
+            .map(|line| line.address)
+            .collect();
+
+        assert_eq!(code, [0xc000]);
+        assert!(d.asm6_round_trip().unwrap().is_exact());
+    }
+
+    #[test]
+    fn jump_table_entries_are_words() {
+        // reset: lda $c00c,y; pha; lda $c00b,y; pha; rts
+        // $c00b: $c00f - 1, $c00f: rts
+        let rom = nrom_with_code(&[
+            0xb9, 0x0c, 0xc0, 0x48, 0xb9, 0x0b, 0xc0, 0x48, 0x60, 0x00, 0x00,
+            0x0e, 0xc0, 0x00, 0x00, 0x60
+        ]);
+        let mut d = NesDisassembler::new(&rom);
+
+        d.run().unwrap();
+
+        let source = d.source(&Asm6::default(), None);
+
+        assert!(source.contains("lda data_C00B+1, y"));
+        assert!(source.contains(".word loc_C00F-1"));
+        assert!(d.asm6_round_trip().unwrap().is_exact());
+
+        d.set_jump_tables(false).run().unwrap();
+
+        assert!(d.jump_tables().is_empty());
+    }
+
+    #[test]
+    fn big_endian_jump_table_is_followed() {
+        // reset: asl; tay; lda $c00b,y; pha; lda $c00c,y; pha; rts
+        // $c00b: $c00f - 1 and $c010 - 1 high byte first, $c00f: nop; rts
+        let rom = nrom_with_code(&[
+            0x0a, 0xa8, 0xb9, 0x0b, 0xc0, 0x48, 0xb9, 0x0c, 0xc0, 0x48, 0x60,
+            0xc0, 0x0e, 0xc0, 0x0f, 0xea, 0x60
+        ]);
+        let mut d = NesDisassembler::new(&rom);
+
+        d.run().unwrap();
+
+        assert_eq!(d.jump_tables()[0].targets, vec![0xc00f, 0xc010]);
+
+        let source = d.source(&Asm6::default(), None);
+
+        assert!(source.contains("lda data_C00B, y"));
+        assert!(source.contains("lda data_C00B+1, y"));
+        assert!(source.contains(".byte >(loc_C00F-1), <(loc_C00F-1)"));
+        assert!(source.contains(".byte >(loc_C010-1), <(loc_C010-1)"));
+        assert!(d.asm6_round_trip().unwrap().is_exact());
+    }
+
+    #[test]
+    fn bytes_after_a_dispatch_are_not_a_table() {
+        // reset: lda $c00c,y; pha; lda $c00b,y; pha; rts
+        // $c00b: $c00b - 1 into the bytes themselves, then $c00a - 1 to a brk
+        for low in [0x0a, 0x09] {
+            let rom = nrom_with_code(&[
+                0xb9, 0x0c, 0xc0, 0x48, 0xb9, 0x0b, 0xc0, 0x48, 0x60, 0x00, 0x00,
+                low, 0xc0
+            ]);
+            let mut d = NesDisassembler::new(&rom);
+
+            d.run().unwrap();
+
+            assert!(d.jump_tables().is_empty());
+        }
+    }
+
+    #[test]
+    fn nesasm_reports_nes2_header() {
+        // NES 2.0, 8K of PRG ROM in the exponent-multiplier form, PRG RAM
+        let mut mem = vec![
+            0x4e, 0x45, 0x53, 0x1a, 0x34, 0x00, 0x00, 0x08,
+            0x00, 0x0f, 0x07, 0x00, 0x00, 0x00, 0x00, 0x00
+        ];
+        mem.resize(16 + 0x2000, 0x00);
+
+        let rom = NesRom::new("game.nes", &mem).unwrap();
+        let mut d = NesDisassembler::new(&rom);
+
+        assert!(d.unsupported().is_empty());
+
+        d.set_dialect(Nesasm);
+
+        assert_eq!(
+            d.unsupported(),
+            vec!["NES 2.0 header", "header bytes 7-15", "ROM size not a multiple of 16K/8K"]
+        );
+    }
+
+    #[test]
+    fn register_writes_are_explained() {
+        // reset: lda #$1e; sta PPUMASK; tax; inx; stx SND_CHN; jmp reset
+        let rom = nrom_with_code(&[
+            0xa9, 0x1e, 0x8d, 0x01, 0x20, 0xaa, 0xe8, 0x8e, 0x15, 0x40, 0x4c, 0x00, 0xc0
+        ]);
+        let mut d = NesDisassembler::new(&rom);
+
+        d.run().unwrap();
+
+        assert!(comment(&d, 0xc002).ends_with("(BG on, sprites on, BG left, sprites left)"));
+        // X is unknown after inx
+        assert!(comment(&d, 0xc007).ends_with("8e 15 40"));
+    }
+}
@@ -0,0 +1,405 @@
+use std::collections::{
+    BTreeMap,
+    BTreeSet,
+    VecDeque
+};
+
+use crate::{
+    disassembler::{
+        bank::{
+            resolve_bank,
+            PrgBank
+        },
+        line::Line
+    },
+    utils::{
+        addressing::AddressingMode,
+        util::json_string
+    }
+};
+
+/// PRG bank and CPU address of a block or a routine
+pub type Node = (usize, u16);
+
+/// Instructions always executed in sequence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub bank: usize,
+    pub address: u16,
+    /// Address following the last instruction
+    pub end: u16,
+    pub name: String,
+    /// Instructions in the output syntax
+    pub instructions: Vec<String>,
+    /// Blocks executed next (taken branch, jump or next instruction)
+    pub successors: Vec<Node>,
+    /// Subroutines called with `jsr`
+    pub calls: Vec<Node>
+}
+
+/// Subroutine or vector handler, with the blocks reached from its entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Routine {
+    pub bank: usize,
+    pub address: u16,
+    pub name: String,
+    pub blocks: Vec<Node>,
+    /// Routines called or jumped to (tail calls)
+    pub calls: Vec<Node>
+}
+
+/// Call graph and control flow graphs of the disassembled code
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::disassembler::NesDisassembler;
+/// use nes_utils::models::nesutil_model::Util;
+/// use nes_utils::rom::rom::NesRom;
+///
+/// // NROM-128, reset: jsr $c006, jmp $c000, $c006: rts
+/// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00];
+/// mem.resize(16, 0x00);
+/// mem.resize(16 + 0x4000, 0xff);
+/// mem[16..23].copy_from_slice(&[0x20, 0x06, 0xc0, 0x4c, 0x00, 0xc0, 0x60]);
+/// mem[16 + 0x3ffa..].copy_from_slice(&[0x00, 0xc0, 0x00, 0xc0, 0x00, 0xc0]);
+///
+/// let rom = NesRom::new("game.nes", &mem).unwrap();
+///
+/// let mut d = NesDisassembler::new(&rom);
+/// d.run().unwrap();
+///
+/// let graph = d.graph();
+/// let reset = graph.routine(0, 0xc000).unwrap();
+///
+/// assert_eq!(reset.calls, vec![(0, 0xc006)]);
+/// assert!(graph.call_graph_dot().contains("r_00_C000 -> r_00_C006;"));
+/// assert!(graph.cfg_dot(reset).contains("    jsr "));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    blocks: BTreeMap<Node, Block>,
+    routines: BTreeMap<Node, Routine>
+}
+
+impl Graph {
+    /// Split the instructions into blocks and group them by routine,
+    /// `entries` are the vectors and the other routine entries
+    pub(super) fn new(
+        banks: &[(PrgBank, Vec<Line>)],
+        entries: &[Node],
+        text: &dyn Fn(&Line) -> String
+    ) -> Self {
+        let prg_banks: Vec<PrgBank> = banks
+            .iter()
+            .map(|(bank, _)| *bank)
+            .collect();
+        let resolve = |bank: &PrgBank, address: u16| -> Option<Node> {
+            resolve_bank(&prg_banks, Some(bank.index), address).map(|bank| (bank.index, address))
+        };
+
+        // First instructions of the blocks
+        let mut leaders = BTreeSet::new();
+        let mut routines: BTreeSet<Node> = entries.iter().copied().collect();
+
+        for (bank, lines) in banks {
+            let mut previous: Option<&Line> = None;
+
+            for line in lines.iter().filter(|line| line.opcode.is_some()) {
+                let contiguous = previous.is_some_and(|previous| {
+                    previous.address.wrapping_add(previous.bytes.len() as u16) == line.address
+                });
+
+                if !contiguous || previous.is_some_and(ends_block) {
+                    leaders.insert((bank.index, line.address));
+                }
+
+                if let Some(target) = line.target().or_else(|| jump_target(line)) {
+                    leaders.extend(resolve(bank, target));
+                }
+                if let Some(target) = call_target(line) {
+                    leaders.extend(resolve(bank, target));
+                    routines.extend(resolve(bank, target));
+                }
+
+                previous = Some(line);
+            }
+        }
+
+        let mut blocks: BTreeMap<Node, Block> = BTreeMap::new();
+
+        for (bank, lines) in banks {
+            let mut current: Option<Block> = None;
+
+            for line in lines {
+                let node = (bank.index, line.address);
+                let is_instruction = line.opcode.is_some();
+
+                // Block ends before a data line or falls into a leader
+                if !is_instruction || leaders.contains(&node) {
+                    if let Some(mut block) = current.take() {
+                        if is_instruction {
+                            block.successors.push(node);
+                        }
+                        blocks.insert((block.bank, block.address), block);
+                    }
+                }
+
+                if !is_instruction {
+                    continue;
+                }
+
+                let block = current.get_or_insert_with(|| Block {
+                    bank: bank.index,
+                    address: line.address,
+                    end: line.address,
+                    name: line.label
+                        .clone()
+                        .unwrap_or_else(|| format!("${:04x}", line.address)),
+                    instructions: Vec::new(),
+                    successors: Vec::new(),
+                    calls: Vec::new()
+                });
+
+                block.end = line.address.wrapping_add(line.bytes.len() as u16);
+                block.instructions.push(text(line));
+                block.calls.extend(call_target(line).and_then(|target| resolve(bank, target)));
+
+                if let Some(target) = line.target().or_else(|| jump_target(line)) {
+                    block.successors.extend(resolve(bank, target));
+                }
+
+                if ends_block(line) {
+                    if falls_through(line) {
+                        block.successors.push((bank.index, block.end));
+                    }
+                    if let Some(block) = current.take() {
+                        blocks.insert((block.bank, block.address), block);
+                    }
+                }
+            }
+
+            if let Some(block) = current.take() {
+                blocks.insert((block.bank, block.address), block);
+            }
+        }
+
+        // Targets inside an instruction or outside of the code
+        let starts: BTreeSet<Node> = blocks.keys().copied().collect();
+
+        for block in blocks.values_mut() {
+            block.successors.retain(|node| starts.contains(node));
+            block.calls.retain(|node| starts.contains(node));
+            block.successors.dedup();
+        }
+
+        routines.retain(|node| starts.contains(node));
+
+        let routines = routines
+            .iter()
+            .map(|node| (*node, Self::trace_routine(&blocks, &routines, *node)))
+            .collect();
+
+        Self {
+            blocks,
+            routines
+        }
+    }
+
+    /// Blocks reached from the entry, other routines are calls
+    fn trace_routine(blocks: &BTreeMap<Node, Block>, entries: &BTreeSet<Node>, entry: Node) -> Routine {
+        let mut seen = BTreeSet::from([entry]);
+        let mut pending = VecDeque::from([entry]);
+        let mut calls = BTreeSet::new();
+
+        while let Some(node) = pending.pop_front() {
+            let block = &blocks[&node];
+
+            calls.extend(block.calls.iter().copied());
+
+            for successor in &block.successors {
+                if entries.contains(successor) && *successor != entry {
+                    calls.insert(*successor);
+                } else if seen.insert(*successor) {
+                    pending.push_back(*successor);
+                }
+            }
+        }
+
+        Routine {
+            bank: entry.0,
+            address: entry.1,
+            name: blocks[&entry].name.clone(),
+            blocks: seen.into_iter().collect(),
+            calls: calls.into_iter().collect()
+        }
+    }
+
+    /// Blocks sorted by bank and address
+    pub fn blocks(&self) -> impl Iterator<Item = &Block> {
+        self.blocks.values()
+    }
+
+    /// Routines sorted by bank and address
+    pub fn routines(&self) -> impl Iterator<Item = &Routine> {
+        self.routines.values()
+    }
+
+    /// Block starting at an address
+    pub fn block(&self, bank: usize, address: u16) -> Option<&Block> {
+        self.blocks.get(&(bank, address))
+    }
+
+    /// Routine entered at an address
+    pub fn routine(&self, bank: usize, address: u16) -> Option<&Routine> {
+        self.routines.get(&(bank, address))
+    }
+
+    /// Graphviz call graph of the routines
+    pub fn call_graph_dot(&self) -> String {
+        let mut ret = String::from("digraph calls {\n    node [shape=box];\n");
+
+        for routine in self.routines() {
+            ret.push_str(&format!(
+                "    {} [label={}];\n",
+                node_id("r", (routine.bank, routine.address)),
+                json_string(&routine.name)
+            ));
+        }
+
+        for routine in self.routines() {
+            for call in &routine.calls {
+                ret.push_str(&format!(
+                    "    {} -> {};\n",
+                    node_id("r", (routine.bank, routine.address)),
+                    node_id("r", *call)
+                ));
+            }
+        }
+
+        ret.push_str("}\n");
+        ret
+    }
+
+    /// Graphviz control flow graph of a routine, with the instructions
+    pub fn cfg_dot(&self, routine: &Routine) -> String {
+        let mut ret = format!(
+            "digraph {} {{\n    node [shape=box, fontname=\"monospace\"];\n",
+            json_string(&routine.name)
+        );
+
+        for node in &routine.blocks {
+            let block = &self.blocks[node];
+            let text: String = std::iter::once(format!("{}:", block.name))
+                .chain(block.instructions.iter().map(|text| format!("    {}", text)))
+                .map(|text| format!("{}\\l", text.replace('\\', "\\\\").replace('"', "\\\"")))
+                .collect();
+
+            ret.push_str(&format!("    {} [label=\"{}\"];\n", node_id("b", *node), text));
+        }
+
+        for node in &routine.blocks {
+            for successor in &self.blocks[node].successors {
+                if routine.blocks.contains(successor) {
+                    ret.push_str(&format!(
+                        "    {} -> {};\n",
+                        node_id("b", *node),
+                        node_id("b", *successor)
+                    ));
+                }
+            }
+        }
+
+        ret.push_str("}\n");
+        ret
+    }
+
+    /// Routines and blocks, the nodes are referenced by their id
+    pub fn to_json(&self) -> String {
+        let ids = |prefix: &str, nodes: &[Node]| -> String {
+            let ids: Vec<String> = nodes
+                .iter()
+                .map(|node| format!("\"{}\"", node_id(prefix, *node)))
+                .collect();
+
+            format!("[{}]", ids.join(", "))
+        };
+
+        let routines: Vec<String> = self.routines()
+            .map(|routine| format!(
+                "    {{ \"id\": \"{}\", \"name\": {}, \"bank\": {}, \"address\": {}, \"blocks\": {}, \"calls\": {} }}",
+                node_id("r", (routine.bank, routine.address)),
+                json_string(&routine.name),
+                routine.bank,
+                routine.address,
+                ids("b", &routine.blocks),
+                ids("r", &routine.calls)
+            ))
+            .collect();
+
+        let blocks: Vec<String> = self.blocks()
+            .map(|block| {
+                let instructions: Vec<String> = block.instructions
+                    .iter()
+                    .map(|text| json_string(text))
+                    .collect();
+
+                format!(
+                    "    {{ \"id\": \"{}\", \"name\": {}, \"bank\": {}, \"address\": {}, \"end\": {}, \"instructions\": [{}], \"successors\": {}, \"calls\": {} }}",
+                    node_id("b", (block.bank, block.address)),
+                    json_string(&block.name),
+                    block.bank,
+                    block.address,
+                    block.end,
+                    instructions.join(", "),
+                    ids("b", &block.successors),
+                    ids("r", &block.calls)
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\n  \"routines\": [\n{}\n  ],\n  \"blocks\": [\n{}\n  ]\n}}\n",
+            routines.join(",\n"),
+            blocks.join(",\n")
+        )
+    }
+}
+
+/// `r_00_C000` for the routines, `b_00_C000` for the blocks
+fn node_id(prefix: &str, (bank, address): Node) -> String {
+    format!("{}_{:02X}_{:04X}", prefix, bank, address)
+}
+
+fn call_target(line: &Line) -> Option<u16> {
+    let opcode = line.opcode.as_ref()?;
+
+    (opcode.mnemonic == "jsr").then(|| line.reference().map(|(address, _)| address))?
+}
+
+fn jump_target(line: &Line) -> Option<u16> {
+    let opcode = line.opcode.as_ref()?;
+
+    match (opcode.mnemonic, opcode.mode) {
+        ("jmp", AddressingMode::Absolute) => line.reference().map(|(address, _)| address),
+        _ => None
+    }
+}
+
+/// Branches, jumps, returns and interrupts end a block
+fn ends_block(line: &Line) -> bool {
+    match &line.opcode {
+        Some(opcode) => opcode.mode == AddressingMode::Relative
+            || matches!(opcode.mnemonic, "jmp" | "rts" | "rti" | "brk" | "jam"),
+        None => true
+    }
+}
+
+/// The next instruction runs after the branches (not taken)
+fn falls_through(line: &Line) -> bool {
+    line.opcode
+        .as_ref()
+        .is_some_and(|opcode| opcode.mode == AddressingMode::Relative)
+}
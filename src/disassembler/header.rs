@@ -2,6 +2,13 @@ use core::fmt;
 use std::collections::HashMap;
 
 use crate::{
+    disassembler::header_info::{
+        HeaderInfo,
+        HeaderFormat,
+        Mirroring,
+        ConsoleType,
+        Timing
+    },
     utils::error::NesError,
     utils::{
         block::Block,
@@ -24,6 +31,34 @@ const NES_HEADER_FIELDS_ORDER: [(&str, usize, usize); 9] = {
     ]
 };
 
+/// Change made to the header bytes by `NesHeader::repair`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderRepair {
+    /// Garbage (e.g. "DiskDude!") cleared from the bytes 7-15
+    ClearedGarbage(Vec<u8>),
+    PrgRomSize { old: usize, new: usize },
+    ChrRomSize { old: usize, new: usize },
+    UpgradedToNes2
+}
+
+impl fmt::Display for HeaderRepair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ClearedGarbage(bytes) => write!(
+                f, "Cleared garbage in bytes 7-15 ({})",
+                vec_bytes_to_string(bytes)
+            ),
+            Self::PrgRomSize { old, new } => write!(
+                f, "PRG ROM size 0x{:x} -> 0x{:x}", old, new
+            ),
+            Self::ChrRomSize { old, new } => write!(
+                f, "CHR ROM size 0x{:x} -> 0x{:x}", old, new
+            ),
+            Self::UpgradedToNes2 => write!(f, "Upgraded iNES 1.0 to NES 2.0")
+        }
+    }
+}
+
 pub struct NesHeader {
     fields: HashMap<String, Block>,
     mem: Vec<u8>
@@ -36,7 +71,19 @@ impl NesHeader {
     pub const CHR_ROM_UNIT_SIZE: usize = 0x2000;
     pub const CHR_ROM_BANK_SIZE: usize = 0x1000;
 
-    pub fn new(mem: &Vec<u8>) -> Self {
+    pub fn new(mem: &[u8]) -> Result<Self, NesError> {
+        if mem.len() < NesHeader::HEADER_SIZE {
+            return Err(NesError::UnexpectedSize {
+                offset: 0,
+                expected: NesHeader::HEADER_SIZE,
+                actual: mem.len()
+            });
+        }
+
+        Ok(Self::from_bytes(&mem[0..NesHeader::HEADER_SIZE]))
+    }
+
+    fn from_bytes(mem: &[u8]) -> Self {
         let mut fields = HashMap::new();
 
         for (name, pos, size) in NES_HEADER_FIELDS_ORDER {
@@ -47,7 +94,26 @@ impl NesHeader {
 
         Self {
             fields,
-            mem: mem[0..16].to_vec()
+            mem: mem.to_vec()
+        }
+    }
+
+    /// Build a header from typed values
+    pub fn from_info(info: &HeaderInfo) -> Self {
+        let mut ret = Self::from_bytes(&info.encode());
+
+        ret.fill_fields();
+        ret
+    }
+
+    /// The 16 header bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.mem
+    }
+
+    fn fill_fields(&mut self) {
+        for (_, block) in self.fields.iter_mut() {
+            block.value_from(&self.mem);
         }
     }
 
@@ -58,35 +124,176 @@ impl NesHeader {
         }
     }
 
-    pub fn is_trainer(&self) -> bool {
-        let f6 = self.field("f6");
+    pub fn is_chr(&self) -> bool {
+        self.chr_rom_size() > 0
+    }
 
-            if let Some(value) = f6.value {
-                return value[0] & 0b0000_0100 != 0
-            }
+    /// Mapper number (12 bits for NES 2.0)
+    pub fn mapper(&self) -> u16 {
+        self.info().mapper
+    }
 
-        false
+    pub fn mirroring(&self) -> Mirroring {
+        Mirroring::from(self.mem[6])
     }
 
-    pub fn is_chr(&self) -> bool {
-        let chr_rom = self.field("len_chr_rom");
+    pub fn has_battery(&self) -> bool {
+        self.mem[6] & 0b0000_0010 != 0
+    }
+
+    pub fn has_trainer(&self) -> bool {
+        self.mem[6] & 0b0000_0100 != 0
+    }
 
-        if let Some(value) = chr_rom.value {
-            return value[0] > 0;
+    pub fn four_screen(&self) -> bool {
+        self.mem[6] & 0b0000_1000 != 0
+    }
+
+    /// PRG ROM size in bytes
+    pub fn prg_rom_size(&self) -> usize {
+        self.info().prg_rom_size
+    }
+
+    /// CHR ROM size in bytes
+    pub fn chr_rom_size(&self) -> usize {
+        self.info().chr_rom_size
+    }
+
+    /// Trainer size in bytes
+    pub fn trainer_size(&self) -> usize {
+        if self.has_trainer() {
+            NesHeader::TRAINER_SIZE
+        } else {
+            0
         }
+    }
 
-        false
+    pub fn console_type(&self) -> ConsoleType {
+        self.info().console_type
+    }
+
+    pub fn tv_system(&self) -> Timing {
+        self.info().timing
+    }
+
+    /// Detect if the header is NES 2.0, iNES 1.0 or archaic iNES
+    pub fn format(&self) -> HeaderFormat {
+        HeaderInfo::format(&self.mem)
+    }
+
+    /// Decode the header bytes into typed values
+    pub fn info(&self) -> HeaderInfo {
+        HeaderInfo::decode(&self.mem)
+    }
+
+    /// Fix the header for a ROM file of `file_size` bytes.
+    ///
+    /// Clears garbage in the bytes 7-15, fixes the PRG/CHR ROM sizes to
+    /// match the file length and, if `upgrade` is set, converts an
+    /// iNES 1.0 header to NES 2.0. Returns every change made.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use nes_utils::disassembler::header::NesHeader;
+    ///
+    /// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x02, 0x01, 0x00];
+    /// mem.extend_from_slice(b"DiskDude!");
+    ///
+    /// let mut header = NesHeader::new(&mem).unwrap();
+    /// let changes = header.repair(16 + 0x8000, false);
+    ///
+    /// assert_eq!(changes.len(), 2);
+    /// assert_eq!(header.chr_rom_size(), 0);
+    /// assert_eq!(&header.bytes()[7..], &[0x00; 9]);
+    ///
+    /// // CHR RAM, the 8 KiB after the PRG ROM are not CHR ROM
+    /// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x02, 0x00];
+    /// mem.resize(16, 0x00);
+    ///
+    /// let mut header = NesHeader::new(&mem).unwrap();
+    ///
+    /// assert!(header.repair(16 + 0xa000, false).is_empty());
+    /// assert_eq!(header.chr_rom_size(), 0);
+    /// ```
+    pub fn repair(&mut self, file_size: usize, upgrade: bool) -> Vec<HeaderRepair> {
+        let mut ret = Vec::new();
+
+        // Archaic headers are mostly ripper signatures like "DiskDude!"
+        if self.format() == HeaderFormat::Archaic && self.mem[7..16].iter().any(|byte| *byte != 0) {
+            ret.push(HeaderRepair::ClearedGarbage(self.mem[7..16].to_vec()));
+            self.mem[7..16].fill(0x00);
+        }
+
+        self.repair_sizes(file_size, &mut ret);
+
+        if upgrade && self.format() == HeaderFormat::INes {
+            let mut info = self.info();
+
+            (info.prg_ram_size, info.prg_nvram_size) = info.prg_ram_sizes();
+            info.format = HeaderFormat::Nes2;
+            self.mem = info.encode().to_vec();
+            ret.push(HeaderRepair::UpgradedToNes2);
+        }
+
+        self.fill_fields();
+
+        ret
+    }
+
+    fn repair_sizes(&mut self, file_size: usize, changes: &mut Vec<HeaderRepair>) {
+        let mut info = self.info();
+        let available = file_size.saturating_sub(NesHeader::HEADER_SIZE + self.trainer_size());
+        let (old_prg, old_chr) = (info.prg_rom_size, info.chr_rom_size);
+
+        if old_prg.saturating_add(old_chr) > available {
+            if old_prg > available {
+                info.prg_rom_size = available - available % NesHeader::PRG_ROM_UNIT_SIZE;
+            }
+
+            let rest = available - info.prg_rom_size;
+            info.chr_rom_size = rest - rest % NesHeader::CHR_ROM_UNIT_SIZE;
+        } else if info.misc_rom_count == 0 && old_chr > 0 {
+            // Extra CHR banks not declared in the header, a CHR RAM game
+            // keeps its trailing data
+            let extra = available - old_prg - old_chr;
+
+            if extra.is_multiple_of(NesHeader::CHR_ROM_UNIT_SIZE) {
+                info.chr_rom_size += extra;
+            }
+        }
+
+        if info.prg_rom_size == old_prg && info.chr_rom_size == old_chr {
+            return;
+        }
+
+        let encoded = info.encode();
+
+        self.mem[4] = encoded[4];
+        self.mem[5] = encoded[5];
+        if info.format == HeaderFormat::Nes2 {
+            self.mem[9] = encoded[9];
+        }
+
+        let info = self.info();
+
+        if info.prg_rom_size != old_prg {
+            changes.push(HeaderRepair::PrgRomSize { old: old_prg, new: info.prg_rom_size });
+        }
+        if info.chr_rom_size != old_chr {
+            changes.push(HeaderRepair::ChrRomSize { old: old_chr, new: info.chr_rom_size });
+        }
     }
 }
 
 impl Header for NesHeader {
-    fn parse(&mut self) -> &mut Self {
-        for (_, block) in self.fields.iter_mut() {
-            block.value_from(&self.mem);
-        }
-        self.check_magic();
-        
-        self
+    fn parse(&mut self) -> Result<&mut Self, NesError> {
+        self.fill_fields();
+        self.check_magic()?;
+
+        Ok(self)
     }
 
     fn dump(&self) -> &Self {
@@ -97,18 +304,20 @@ impl Header for NesHeader {
         self
     }
 
-    fn check_magic(&self) {
-        let magic = self.fields.get("magic");
-        let correct = vec![0x4e, 0x45, 0x53, 0x1a];
-
-        match magic {
-            Some(block) => {
-                if block.value != Some(correct) {
-                    panic!("{}", NesError::WrongNesFormat)
-                }
+    fn check_magic(&self) -> Result<(), NesError> {
+        let magic = match self.fields.get("magic") {
+            Some(block) => match &block.value {
+                Some(value) => value,
+                None => return Err(NesError::HeaderNotParsed)
             },
-            None => panic!("{}", NesError::HeaderNotParsed)
+            None => return Err(NesError::HeaderNotParsed)
+        };
+
+        if magic[..] != HeaderInfo::MAGIC {
+            return Err(NesError::WrongNesFormat { magic: magic.clone() });
         }
+
+        Ok(())
     }
 }
 
@@ -116,15 +325,8 @@ impl fmt::Display for NesHeader {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut msg = Vec::<String>::new();
 
-        for (name, _, _) in NES_HEADER_FIELDS_ORDER {
-            let bytes = match self.fields.get(name) {
-                Some(field) => match &field.value {
-                    Some(v) => v.clone(),
-                    None => vec![0x00]
-                },
-                None => continue
-            };
-
+        for (_, pos, size) in NES_HEADER_FIELDS_ORDER {
+            let bytes = self.mem[pos..pos + size].to_vec();
             let bytes_str = vec_bytes_to_string(&bytes);
             let line = format!("hex {}", bytes_str);
 
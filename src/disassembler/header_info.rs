@@ -0,0 +1,610 @@
+use crate::disassembler::header::NesHeader;
+
+/// Header format detected from the identifier bits of the byte 7
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderFormat {
+    Archaic,
+    INes,
+    Nes2
+}
+
+/// Nametable mirroring (byte 6, bits 0 and 3)
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::header_info::{HeaderInfo, Mirroring};
+///
+/// // Four screen, the bit 0 is kept
+/// let mirroring = Mirroring::from(0b0000_1001);
+///
+/// assert_eq!(mirroring, Mirroring::FourScreen { vertical: true });
+/// assert_eq!(u8::from(mirroring), 0b0000_1001);
+///
+/// let bytes = [
+///     0x4e, 0x45, 0x53, 0x1a, 0x02, 0x01, 0x09, 0x00,
+///     0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+/// ];
+///
+/// assert_eq!(HeaderInfo::decode(&bytes).encode(), bytes);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mirroring {
+    Horizontal,
+    Vertical,
+    /// `vertical` is the bit 0, ignored by the hardware
+    FourScreen { vertical: bool }
+}
+
+impl From<u8> for Mirroring {
+    fn from(value: u8) -> Self {
+        let vertical = value & 0b0000_0001 != 0;
+
+        if value & 0b0000_1000 != 0 {
+            Self::FourScreen { vertical }
+        } else if vertical {
+            Self::Vertical
+        } else {
+            Self::Horizontal
+        }
+    }
+}
+
+impl From<Mirroring> for u8 {
+    fn from(value: Mirroring) -> Self {
+        match value {
+            Mirroring::Horizontal => 0b0000_0000,
+            Mirroring::Vertical => 0b0000_0001,
+            Mirroring::FourScreen { vertical } => 0b0000_1000 | vertical as u8
+        }
+    }
+}
+
+/// CPU/PPU timing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    Ntsc,
+    Pal,
+    MultiRegion,
+    Dendy
+}
+
+impl From<u8> for Timing {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0 => Self::Ntsc,
+            1 => Self::Pal,
+            2 => Self::MultiRegion,
+            _ => Self::Dendy
+        }
+    }
+}
+
+impl From<Timing> for u8 {
+    fn from(value: Timing) -> Self {
+        match value {
+            Timing::Ntsc => 0,
+            Timing::Pal => 1,
+            Timing::MultiRegion => 2,
+            Timing::Dendy => 3
+        }
+    }
+}
+
+/// Console type (byte 7, bits 0-1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsoleType {
+    Nes,
+    VsSystem,
+    Playchoice10,
+    Extended
+}
+
+impl From<u8> for ConsoleType {
+    fn from(value: u8) -> Self {
+        match value & 0b11 {
+            0 => Self::Nes,
+            1 => Self::VsSystem,
+            2 => Self::Playchoice10,
+            _ => Self::Extended
+        }
+    }
+}
+
+impl From<ConsoleType> for u8 {
+    fn from(value: ConsoleType) -> Self {
+        match value {
+            ConsoleType::Nes => 0,
+            ConsoleType::VsSystem => 1,
+            ConsoleType::Playchoice10 => 2,
+            ConsoleType::Extended => 3
+        }
+    }
+}
+
+/// Vs. System PPU type (byte 13, bits 0-3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VsPpuType {
+    Rp2c03b,
+    Rp2c03g,
+    Rp2c04_0001,
+    Rp2c04_0002,
+    Rp2c04_0003,
+    Rp2c04_0004,
+    Rc2c03b,
+    Rc2c03c,
+    Rc2c05_01,
+    Rc2c05_02,
+    Rc2c05_03,
+    Rc2c05_04,
+    Rc2c05_05,
+    Reserved(u8)
+}
+
+impl From<u8> for VsPpuType {
+    fn from(value: u8) -> Self {
+        match value & 0x0f {
+            0x0 => Self::Rp2c03b,
+            0x1 => Self::Rp2c03g,
+            0x2 => Self::Rp2c04_0001,
+            0x3 => Self::Rp2c04_0002,
+            0x4 => Self::Rp2c04_0003,
+            0x5 => Self::Rp2c04_0004,
+            0x6 => Self::Rc2c03b,
+            0x7 => Self::Rc2c03c,
+            0x8 => Self::Rc2c05_01,
+            0x9 => Self::Rc2c05_02,
+            0xa => Self::Rc2c05_03,
+            0xb => Self::Rc2c05_04,
+            0xc => Self::Rc2c05_05,
+            other => Self::Reserved(other)
+        }
+    }
+}
+
+impl From<VsPpuType> for u8 {
+    fn from(value: VsPpuType) -> Self {
+        match value {
+            VsPpuType::Rp2c03b => 0x0,
+            VsPpuType::Rp2c03g => 0x1,
+            VsPpuType::Rp2c04_0001 => 0x2,
+            VsPpuType::Rp2c04_0002 => 0x3,
+            VsPpuType::Rp2c04_0003 => 0x4,
+            VsPpuType::Rp2c04_0004 => 0x5,
+            VsPpuType::Rc2c03b => 0x6,
+            VsPpuType::Rc2c03c => 0x7,
+            VsPpuType::Rc2c05_01 => 0x8,
+            VsPpuType::Rc2c05_02 => 0x9,
+            VsPpuType::Rc2c05_03 => 0xa,
+            VsPpuType::Rc2c05_04 => 0xb,
+            VsPpuType::Rc2c05_05 => 0xc,
+            VsPpuType::Reserved(other) => other & 0x0f
+        }
+    }
+}
+
+/// Vs. System hardware type (byte 13, bits 4-7)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VsHardwareType {
+    Unisystem,
+    UnisystemRbiBaseball,
+    UnisystemTkoBoxing,
+    UnisystemSuperXevious,
+    UnisystemIceClimber,
+    DualSystem,
+    DualSystemRaidOnBungelingBay,
+    Reserved(u8)
+}
+
+impl From<u8> for VsHardwareType {
+    fn from(value: u8) -> Self {
+        match value & 0x0f {
+            0x0 => Self::Unisystem,
+            0x1 => Self::UnisystemRbiBaseball,
+            0x2 => Self::UnisystemTkoBoxing,
+            0x3 => Self::UnisystemSuperXevious,
+            0x4 => Self::UnisystemIceClimber,
+            0x5 => Self::DualSystem,
+            0x6 => Self::DualSystemRaidOnBungelingBay,
+            other => Self::Reserved(other)
+        }
+    }
+}
+
+impl From<VsHardwareType> for u8 {
+    fn from(value: VsHardwareType) -> Self {
+        match value {
+            VsHardwareType::Unisystem => 0x0,
+            VsHardwareType::UnisystemRbiBaseball => 0x1,
+            VsHardwareType::UnisystemTkoBoxing => 0x2,
+            VsHardwareType::UnisystemSuperXevious => 0x3,
+            VsHardwareType::UnisystemIceClimber => 0x4,
+            VsHardwareType::DualSystem => 0x5,
+            VsHardwareType::DualSystemRaidOnBungelingBay => 0x6,
+            VsHardwareType::Reserved(other) => other & 0x0f
+        }
+    }
+}
+
+/// Vs. System PPU and hardware types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VsSystemType {
+    pub ppu: VsPpuType,
+    pub hardware: VsHardwareType
+}
+
+/// Extended console type (byte 13, bits 0-3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtendedConsoleType {
+    Nes,
+    VsSystem,
+    Playchoice10,
+    FamicloneDecimalMode,
+    NesWithEpsm,
+    Vt01,
+    Vt02,
+    Vt03,
+    Vt09,
+    Vt32,
+    Vt369,
+    Um6578,
+    FamicomNetworkSystem,
+    Reserved(u8)
+}
+
+impl From<u8> for ExtendedConsoleType {
+    fn from(value: u8) -> Self {
+        match value & 0x0f {
+            0x0 => Self::Nes,
+            0x1 => Self::VsSystem,
+            0x2 => Self::Playchoice10,
+            0x3 => Self::FamicloneDecimalMode,
+            0x4 => Self::NesWithEpsm,
+            0x5 => Self::Vt01,
+            0x6 => Self::Vt02,
+            0x7 => Self::Vt03,
+            0x8 => Self::Vt09,
+            0x9 => Self::Vt32,
+            0xa => Self::Vt369,
+            0xb => Self::Um6578,
+            0xc => Self::FamicomNetworkSystem,
+            other => Self::Reserved(other)
+        }
+    }
+}
+
+impl From<ExtendedConsoleType> for u8 {
+    fn from(value: ExtendedConsoleType) -> Self {
+        match value {
+            ExtendedConsoleType::Nes => 0x0,
+            ExtendedConsoleType::VsSystem => 0x1,
+            ExtendedConsoleType::Playchoice10 => 0x2,
+            ExtendedConsoleType::FamicloneDecimalMode => 0x3,
+            ExtendedConsoleType::NesWithEpsm => 0x4,
+            ExtendedConsoleType::Vt01 => 0x5,
+            ExtendedConsoleType::Vt02 => 0x6,
+            ExtendedConsoleType::Vt03 => 0x7,
+            ExtendedConsoleType::Vt09 => 0x8,
+            ExtendedConsoleType::Vt32 => 0x9,
+            ExtendedConsoleType::Vt369 => 0xa,
+            ExtendedConsoleType::Um6578 => 0xb,
+            ExtendedConsoleType::FamicomNetworkSystem => 0xc,
+            ExtendedConsoleType::Reserved(other) => other & 0x0f
+        }
+    }
+}
+
+/// Typed content of a 16 bytes NES header.
+///
+/// NES 2.0 headers are fully decoded, iNES 1.0 and archaic iNES
+/// headers only fill the fields they are able to describe.
+///
+/// It can also be encoded back into header bytes with `encode`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::header_info::{HeaderInfo, HeaderFormat};
+///
+/// let bytes = [
+///     0x4e, 0x45, 0x53, 0x1a, 0x02, 0x01, 0x01, 0x08,
+///     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+/// ];
+/// let info = HeaderInfo::decode(&bytes);
+///
+/// assert_eq!(info.format, HeaderFormat::Nes2);
+/// assert_eq!(info.prg_rom_size, 0x8000);
+/// assert_eq!(info.encode(), bytes);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderInfo {
+    pub format: HeaderFormat,
+    pub mapper: u16,
+    pub submapper: u8,
+    pub mirroring: Mirroring,
+    pub battery: bool,
+    pub trainer: bool,
+    pub prg_rom_size: usize,
+    pub chr_rom_size: usize,
+    /// As stored, `0` for an iNES 1.0 byte 8 of zero, see `prg_ram_sizes`
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+    pub chr_ram_size: usize,
+    pub chr_nvram_size: usize,
+    pub timing: Timing,
+    pub console_type: ConsoleType,
+    pub vs_system: Option<VsSystemType>,
+    pub extended_console_type: Option<ExtendedConsoleType>,
+    pub misc_rom_count: u8,
+    pub expansion_device: u8
+}
+
+/// Size in bytes of a NES 2.0 ROM area using the exponent-multiplier notation
+fn exponent_size(lsb: u8) -> usize {
+    let exponent = (lsb >> 2) as u32;
+    let multiplier = (lsb & 0b11) as usize * 2 + 1;
+
+    match 1usize.checked_shl(exponent) {
+        Some(value) => value.saturating_mul(multiplier),
+        None => usize::MAX
+    }
+}
+
+/// Size in bytes of a NES 2.0 ROM area
+fn rom_size(lsb: u8, msb: u8, unit: usize) -> usize {
+    if msb == 0x0f {
+        return exponent_size(lsb);
+    }
+
+    ((msb as usize) << 8 | lsb as usize) * unit
+}
+
+/// NES 2.0 ROM size bytes (LSB, MSB nibble) for a size in bytes
+fn encode_rom_size(size: usize, unit: usize) -> (u8, u8) {
+    let units = size / unit;
+
+    if size.is_multiple_of(unit) && units < 0xf00 {
+        return ((units & 0xff) as u8, (units >> 8) as u8);
+    }
+
+    // Smallest exponent-multiplier notation holding the size
+    let mut best = (0xff, 0x0f);
+    let mut best_size = usize::MAX;
+
+    for lsb in 0..=0xffu8 {
+        let value = exponent_size(lsb);
+
+        if value >= size && value < best_size {
+            best = (lsb, 0x0f);
+            best_size = value;
+        }
+    }
+
+    best
+}
+
+/// Size in bytes of a NES 2.0 RAM area from its shift count
+fn shift_size(shift: u8) -> usize {
+    match shift & 0x0f {
+        0 => 0,
+        value => 64 << value
+    }
+}
+
+/// NES 2.0 shift count holding a RAM area size in bytes
+fn encode_shift(size: usize) -> u8 {
+    if size == 0 {
+        return 0;
+    }
+
+    (1..=0x0f)
+        .find(|shift| 64usize << shift >= size)
+        .unwrap_or(0x0f)
+}
+
+impl HeaderInfo {
+    pub const PRG_RAM_UNIT_SIZE: usize = 0x2000;
+    pub const MAGIC: [u8; 4] = [0x4e, 0x45, 0x53, 0x1a];
+
+    /// Detect the header format from the 16 header bytes
+    pub fn format(mem: &[u8]) -> HeaderFormat {
+        match mem[7] & 0b0000_1100 {
+            0b0000_1000 => HeaderFormat::Nes2,
+            0b0000_0000 if mem[12..16].iter().all(|byte| *byte == 0) => HeaderFormat::INes,
+            _ => HeaderFormat::Archaic
+        }
+    }
+
+    /// Decode the 16 header bytes
+    pub fn decode(mem: &[u8]) -> Self {
+        match Self::format(mem) {
+            HeaderFormat::Nes2 => Self::decode_nes2(mem),
+            HeaderFormat::INes => Self::decode_ines(mem),
+            HeaderFormat::Archaic => Self::decode_archaic(mem)
+        }
+    }
+
+    fn decode_archaic(mem: &[u8]) -> Self {
+        Self {
+            format: HeaderFormat::Archaic,
+            mapper: (mem[6] >> 4) as u16,
+            submapper: 0,
+            mirroring: Mirroring::from(mem[6]),
+            battery: mem[6] & 0b0000_0010 != 0,
+            trainer: mem[6] & 0b0000_0100 != 0,
+            prg_rom_size: mem[4] as usize * NesHeader::PRG_ROM_UNIT_SIZE,
+            chr_rom_size: mem[5] as usize * NesHeader::CHR_ROM_UNIT_SIZE,
+            prg_ram_size: 0,
+            prg_nvram_size: 0,
+            chr_ram_size: 0,
+            chr_nvram_size: 0,
+            timing: Timing::Ntsc,
+            console_type: ConsoleType::Nes,
+            vs_system: None,
+            extended_console_type: None,
+            misc_rom_count: 0,
+            expansion_device: 0
+        }
+    }
+
+    fn decode_ines(mem: &[u8]) -> Self {
+        let mut ret = Self::decode_archaic(mem);
+        let prg_ram_size = mem[8] as usize * Self::PRG_RAM_UNIT_SIZE;
+
+        ret.format = HeaderFormat::INes;
+        ret.mapper |= (mem[7] & 0xf0) as u16;
+
+        if ret.battery {
+            ret.prg_nvram_size = prg_ram_size;
+        } else {
+            ret.prg_ram_size = prg_ram_size;
+        }
+
+        // CHR RAM is implied when there is no CHR ROM
+        if ret.chr_rom_size == 0 {
+            ret.chr_ram_size = NesHeader::CHR_ROM_UNIT_SIZE;
+        }
+
+        ret.timing = Timing::from(mem[9] & 1);
+        ret.console_type = if mem[7] & 0b01 != 0 {
+            ConsoleType::VsSystem
+        } else if mem[7] & 0b10 != 0 {
+            ConsoleType::Playchoice10
+        } else {
+            ConsoleType::Nes
+        };
+
+        ret
+    }
+
+    fn decode_nes2(mem: &[u8]) -> Self {
+        let console_type = ConsoleType::from(mem[7]);
+        let vs_system = match console_type {
+            ConsoleType::VsSystem => Some(VsSystemType {
+                ppu: VsPpuType::from(mem[13]),
+                hardware: VsHardwareType::from(mem[13] >> 4)
+            }),
+            _ => None
+        };
+        let extended_console_type = match console_type {
+            ConsoleType::Extended => Some(ExtendedConsoleType::from(mem[13])),
+            _ => None
+        };
+
+        Self {
+            format: HeaderFormat::Nes2,
+            mapper: (mem[6] >> 4) as u16
+                | (mem[7] & 0xf0) as u16
+                | ((mem[8] & 0x0f) as u16) << 8,
+            submapper: mem[8] >> 4,
+            mirroring: Mirroring::from(mem[6]),
+            battery: mem[6] & 0b0000_0010 != 0,
+            trainer: mem[6] & 0b0000_0100 != 0,
+            prg_rom_size: rom_size(mem[4], mem[9] & 0x0f, NesHeader::PRG_ROM_UNIT_SIZE),
+            chr_rom_size: rom_size(mem[5], mem[9] >> 4, NesHeader::CHR_ROM_UNIT_SIZE),
+            prg_ram_size: shift_size(mem[10]),
+            prg_nvram_size: shift_size(mem[10] >> 4),
+            chr_ram_size: shift_size(mem[11]),
+            chr_nvram_size: shift_size(mem[11] >> 4),
+            timing: Timing::from(mem[12]),
+            console_type,
+            vs_system,
+            extended_console_type,
+            misc_rom_count: mem[14] & 0b11,
+            expansion_device: mem[15] & 0b0011_1111
+        }
+    }
+
+    /// PRG RAM and battery-backed PRG RAM sizes, an iNES 1.0 header
+    /// without any means 8 KiB for compatibility
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use nes_utils::disassembler::header_info::HeaderInfo;
+    ///
+    /// // iNES 1.0 with a byte 8 of zero and a battery
+    /// let bytes = [
+    ///     0x4e, 0x45, 0x53, 0x1a, 0x02, 0x01, 0x02, 0x00,
+    ///     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00
+    /// ];
+    /// let info = HeaderInfo::decode(&bytes);
+    ///
+    /// assert_eq!(info.prg_ram_sizes(), (0, 0x2000));
+    /// assert_eq!(info.encode(), bytes);
+    /// ```
+    pub fn prg_ram_sizes(&self) -> (usize, usize) {
+        let declared = self.prg_ram_size + self.prg_nvram_size > 0;
+
+        match (self.format, declared, self.battery) {
+            (HeaderFormat::INes, false, false) => (Self::PRG_RAM_UNIT_SIZE, 0),
+            (HeaderFormat::INes, false, true) => (0, Self::PRG_RAM_UNIT_SIZE),
+            _ => (self.prg_ram_size, self.prg_nvram_size)
+        }
+    }
+
+    /// Encode into the 16 header bytes, using the format in `self.format`
+    pub fn encode(&self) -> [u8; NesHeader::HEADER_SIZE] {
+        let mut mem = [0x00; NesHeader::HEADER_SIZE];
+
+        mem[0..4].copy_from_slice(&Self::MAGIC);
+        mem[6] = ((self.mapper & 0x0f) as u8) << 4
+            | u8::from(self.mirroring)
+            | (self.battery as u8) << 1
+            | (self.trainer as u8) << 2;
+        mem[7] = (self.mapper & 0xf0) as u8;
+
+        match self.format {
+            HeaderFormat::Nes2 => self.encode_nes2(&mut mem),
+            HeaderFormat::INes => self.encode_ines(&mut mem),
+            HeaderFormat::Archaic => {
+                mem[4] = (self.prg_rom_size / NesHeader::PRG_ROM_UNIT_SIZE).min(0xff) as u8;
+                mem[5] = (self.chr_rom_size / NesHeader::CHR_ROM_UNIT_SIZE).min(0xff) as u8;
+                mem[7] = 0;
+            }
+        };
+
+        mem
+    }
+
+    fn encode_ines(&self, mem: &mut [u8]) {
+        let prg_ram_size = self.prg_ram_size + self.prg_nvram_size;
+
+        mem[4] = (self.prg_rom_size / NesHeader::PRG_ROM_UNIT_SIZE).min(0xff) as u8;
+        mem[5] = (self.chr_rom_size / NesHeader::CHR_ROM_UNIT_SIZE).min(0xff) as u8;
+        mem[7] |= match self.console_type {
+            ConsoleType::VsSystem => 0b01,
+            ConsoleType::Playchoice10 => 0b10,
+            _ => 0b00
+        };
+        mem[8] = (prg_ram_size / Self::PRG_RAM_UNIT_SIZE).min(0xff) as u8;
+        mem[9] = (self.timing == Timing::Pal) as u8;
+    }
+
+    fn encode_nes2(&self, mem: &mut [u8]) {
+        let (prg_lsb, prg_msb) = encode_rom_size(self.prg_rom_size, NesHeader::PRG_ROM_UNIT_SIZE);
+        let (chr_lsb, chr_msb) = encode_rom_size(self.chr_rom_size, NesHeader::CHR_ROM_UNIT_SIZE);
+
+        mem[4] = prg_lsb;
+        mem[5] = chr_lsb;
+        mem[7] |= 0b0000_1000 | u8::from(self.console_type);
+        mem[8] = self.submapper << 4 | ((self.mapper >> 8) & 0x0f) as u8;
+        mem[9] = chr_msb << 4 | prg_msb;
+        mem[10] = encode_shift(self.prg_nvram_size) << 4 | encode_shift(self.prg_ram_size);
+        mem[11] = encode_shift(self.chr_nvram_size) << 4 | encode_shift(self.chr_ram_size);
+        mem[12] = u8::from(self.timing);
+        mem[13] = match (self.vs_system, self.extended_console_type) {
+            (Some(vs), _) => u8::from(vs.hardware) << 4 | u8::from(vs.ppu),
+            (None, Some(console)) => u8::from(console),
+            (None, None) => 0
+        };
+        mem[14] = self.misc_rom_count & 0b11;
+        mem[15] = self.expansion_device & 0b0011_1111;
+    }
+}
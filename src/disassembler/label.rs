@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+
+use crate::disassembler::bank::{
+    is_ambiguous,
+    resolve_bank,
+    PrgBank
+};
+
+/// Why an address has been labeled, the first variants win
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LabelKind {
+    /// Named by a symbol file
+    Symbol,
+    /// Target of a CPU vector (`reset`, `nmi`, `irq`)
+    Vector(&'static str),
+    /// Target of a `jsr`
+    Subroutine,
+    /// Target of a `jmp` or a branch
+    Location,
+    /// Address read or written by an instruction
+    Data
+}
+
+impl LabelKind {
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Self::Symbol => "sym",
+            Self::Vector(name) => name,
+            Self::Subroutine => "sub",
+            Self::Location => "loc",
+            Self::Data => "data"
+        }
+    }
+
+    /// Lowest rank wins when an address is labeled twice
+    fn rank(&self) -> usize {
+        match self {
+            Self::Symbol => 0,
+            Self::Vector("reset") => 1,
+            Self::Vector("nmi") => 2,
+            Self::Vector(_) => 3,
+            Self::Subroutine => 4,
+            Self::Location => 5,
+            Self::Data => 6
+        }
+    }
+}
+
+/// A named address of a PRG bank
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub bank: usize,
+    pub address: u16,
+    pub kind: LabelKind,
+    pub name: String
+}
+
+impl Label {
+    /// The bank is in the name if other banks may be mapped at the address
+    fn new(bank: &PrgBank, address: u16, kind: LabelKind, ambiguous: bool) -> Self {
+        let name = match kind {
+            LabelKind::Vector(name) => name.to_string(),
+            _ if ambiguous => format!("{}_{:02X}_{:04X}", kind.prefix(), bank.index, address),
+            _ => format!("{}_{:04X}", kind.prefix(), address)
+        };
+
+        Self {
+            bank: bank.index,
+            address,
+            kind,
+            name
+        }
+    }
+}
+
+/// Labels of the PRG ROM, indexed by bank and CPU address
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::bank::prg_banks;
+/// use nes_utils::disassembler::label::{LabelKind, Labels};
+/// use nes_utils::rom::rom::NesRom;
+///
+/// // NROM-128
+/// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00];
+/// mem.resize(16 + 0x4000, 0x00);
+///
+/// let rom = NesRom::new("game.nes", &mem).unwrap();
+/// let banks = prg_banks(&rom);
+///
+/// let mut labels = Labels::new();
+/// labels.insert(&banks, None, 0xc123, LabelKind::Location);
+/// labels.insert(&banks, None, 0xc123, LabelKind::Subroutine);
+/// labels.insert(&banks, None, 0x0300, LabelKind::Data);
+///
+/// assert_eq!(labels.get(&banks, None, 0xc123).unwrap().name, "sub_C123");
+/// assert!(labels.get(&banks, None, 0x0300).is_none());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Labels {
+    labels: BTreeMap<(usize, u16), Label>
+}
+
+impl Labels {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Label an address seen from the bank `from`, ignored outside of the PRG ROM
+    pub fn insert(
+        &mut self,
+        banks: &[PrgBank],
+        from: Option<usize>,
+        address: u16,
+        kind: LabelKind
+    ) {
+        let bank = match resolve_bank(banks, from, address) {
+            Some(bank) => bank,
+            None => return
+        };
+
+        let label = Label::new(bank, address, kind, is_ambiguous(banks, address));
+
+        self.labels
+            .entry((bank.index, address))
+            .and_modify(|current| {
+                if label.kind.rank() < current.kind.rank() {
+                    *current = label.clone();
+                }
+            })
+            .or_insert(label);
+    }
+
+    /// Name an address of a bank, over any other label
+    pub fn insert_symbol(&mut self, bank: &PrgBank, address: u16, name: &str) {
+        let label = Label {
+            bank: bank.index,
+            address,
+            kind: LabelKind::Symbol,
+            name: name.to_string()
+        };
+
+        self.labels.insert((bank.index, address), label);
+    }
+
+    /// Label of an address seen from the bank `from`
+    pub fn get(&self, banks: &[PrgBank], from: Option<usize>, address: u16) -> Option<&Label> {
+        let bank = resolve_bank(banks, from, address)?;
+
+        self.labels.get(&(bank.index, address))
+    }
+
+    /// Keep the labels matching the predicate
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&Label) -> bool
+    {
+        self.labels.retain(|_, label| f(label));
+    }
+
+    /// Labeled addresses of a bank
+    pub fn bank_addresses(&self, bank: usize) -> impl Iterator<Item = u16> + '_ {
+        self.labels
+            .range((bank, 0)..=(bank, u16::MAX))
+            .map(|(_, label)| label.address)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Label> {
+        self.labels.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
@@ -1,62 +1,100 @@
 use crate::utils::{
+    addressing::{
+        relative_target,
+        AddressingMode
+    },
     opcode::OpCode,
-    util::{
-        u16_from_mem,
-        unwrap_str, vec_bytes_to_string
-    }, registers::get_mapped_register
+    util::u16_from_mem,
+    registers::get_mapped_register
 };
 
-use std::fmt;
-use std::cmp::Ordering;
-
-use super::disassembler::EquConst;
+use super::{
+    disassembler::EquConst,
+    label::LabelKind,
+    table::{
+        TableEntry,
+        TableKind
+    }
+};
 
+#[derive(Clone)]
 pub struct Line {
+    /// CPU address of the first byte
+    pub address: u16,
     pub bytes: Vec<u8>,
-    pub opcode: OpCode,
+    /// `None` for a data line
+    pub opcode: Option<OpCode>,
     pub label: Option<String>,
     pub fmt_arg: String,
-    pub comment: Option<String>
+    pub comment: Option<String>,
+    /// Why the bytes could not be decoded, written with `.byte`
+    pub note: Option<String>,
+    /// Comment of a symbol file, written above the line
+    pub symbol_comment: Option<String>,
+    /// References to the line, written above it
+    pub xref_comment: Option<String>,
+    /// Code address of a jump table entry, written as a word
+    pub table: Option<TableEntry>
 }
 
 impl Line {
-    pub fn len(&self) -> usize {
-        let mut ret: usize = 0;
-
-        if let Some(comment) = &self.comment {
-            ret += comment.len();
+    pub fn instruction(address: u16, bytes: &[u8], opcode: OpCode) -> Self {
+        Self {
+            address,
+            bytes: bytes.to_vec(),
+            opcode: Some(opcode),
+            label: None,
+            fmt_arg: String::from(""),
+            comment: None,
+            note: None,
+            symbol_comment: None,
+            xref_comment: None,
+            table: None
         }
-        if let Some(label) = &self.label {
-            ret += label.len();
+    }
+
+    pub fn data(address: u16, bytes: &[u8]) -> Self {
+        Self {
+            address,
+            bytes: bytes.to_vec(),
+            opcode: None,
+            label: None,
+            fmt_arg: String::from(""),
+            comment: None,
+            note: None,
+            symbol_comment: None,
+            xref_comment: None,
+            table: None
         }
+    }
 
-        ret += self.opcode.mnemonic.len() + self.fmt_arg.len();
+    /// Data line for bytes that are not a valid instruction
+    pub fn undecodable(address: u16, bytes: &[u8], note: &str) -> Self {
+        let mut ret = Self::data(address, bytes);
 
+        ret.note = Some(note.to_string());
         ret
     }
 
-    fn fmt_special(&mut self) -> bool {
-        let arg_bytes = self.bytes[1..].to_vec();
-
-        match self.opcode.mnemonic {
-            "hex" => {
-                self.fmt_arg = vec_bytes_to_string(&arg_bytes);
-            },
-            _ => return false
-        };
+    /// Entry of a table of words, `fmt_arg` is the stored address
+    pub fn table_entry(address: u16, bytes: &[u8], entry: TableEntry) -> Self {
+        let mut ret = Self::data(address, bytes);
 
-        true
+        ret.table = Some(entry);
+        ret.fmt_arg = format!("${:04x}", ret.table_word().unwrap_or(0));
+        ret
     }
 
+    /// Format the operand, data lines are formatted by the dialect
     pub fn fmt(&mut self) -> Option<EquConst> {
-        if self.fmt_special() {
-            return None
-        }
-
         self.fmt_arg()
     }
 
     fn fmt_arg(&mut self) -> Option<EquConst> {
+        let mode = match &self.opcode {
+            Some(opcode) => opcode.mode,
+            None => return None
+        };
         let mut arg_bytes = self.bytes[1..].to_vec();
 
         // Because of the endianess (little)
@@ -64,17 +102,20 @@ impl Line {
 
 
         // Normal format
-        let mut arg_str = OpCode::arg_to_string(&arg_bytes);
+        let mut arg_str = match self.target() {
+            Some(target) if mode == AddressingMode::Relative => format!("{:04x}", target),
+            _ => OpCode::arg_to_string(&arg_bytes)
+        };
         let mut ret = None;
 
-        arg_str = self.opcode.mode.fmt_arg(&arg_str);
+        arg_str = mode.fmt_arg(&arg_str);
 
         arg_str = match self.arg_to_le_u16() {
             Some(value) => {
                 match get_mapped_register(value) {
                     Some(name) => {
                         ret = Some((value, name.clone()));
-                        self.opcode.mode.fmt_arg_with_reg(&name)
+                        mode.fmt_arg_with_reg(&name)
                     },
                     None => arg_str
                 }
@@ -87,58 +128,109 @@ impl Line {
         ret
     }
 
-    fn arg_to_le_u16(&mut self) -> Option<u16> {
-        let arg_bytes = self.bytes[1..].to_vec();
+    /// CPU address resolved from a branch operand
+    pub fn target(&self) -> Option<u16> {
+        match &self.opcode {
+            Some(opcode) if opcode.mode == AddressingMode::Relative && self.bytes.len() == 2 => {
+                Some(relative_target(self.address, self.bytes[1]))
+            },
+            _ => None
+        }
+    }
 
-        if arg_bytes.len() < 2 {
-            return None;
+    /// Address referenced by the operand and how it is used
+    pub fn reference(&self) -> Option<(u16, LabelKind)> {
+        if let Some(entry) = self.table {
+            let address = self.table_word()?;
+
+            return Some((address.wrapping_add(entry.kind.bias()), LabelKind::Location));
         }
 
-        Some(u16_from_mem(arg_bytes[0], arg_bytes[1]))
-    }
-}
+        let opcode = self.opcode.as_ref()?;
 
-impl Eq for Line { }
+        if let Some(target) = self.target() {
+            return Some((target, LabelKind::Location));
+        }
+
+        let address = match opcode.mode {
+            AddressingMode::Absolute
+            | AddressingMode::AbsoluteX
+            | AddressingMode::AbsoluteY
+            | AddressingMode::Indirect => self.arg_to_le_u16()?,
+            _ => return None
+        };
 
-impl Ord for Line {
-    fn cmp(&self, other: &Self) -> Ordering {
-        let cmp: i32 = self.len() as i32 - other.len() as i32;
-        let ret: Ordering;
+        let kind = match (opcode.mnemonic, opcode.mode) {
+            ("jsr", _) => LabelKind::Subroutine,
+            ("jmp", AddressingMode::Absolute) => LabelKind::Location,
+            _ => LabelKind::Data
+        };
 
-        if cmp < 0 {
-            ret = Ordering::Less;
-        } else if cmp > 0 {
-            ret = Ordering::Greater
-        } else {
-            ret = Ordering::Equal
+        Some((address, kind))
+    }
+
+    /// Memory address of the operand, zero page included
+    pub fn operand_address(&self) -> Option<u16> {
+        let opcode = self.opcode.as_ref()?;
+
+        match opcode.mode {
+            AddressingMode::ZeroPage
+            | AddressingMode::ZeroPageX
+            | AddressingMode::ZeroPageY
+            | AddressingMode::IndexedIndirect
+            | AddressingMode::IndirectIndexed => self.bytes.get(1).map(|byte| *byte as u16),
+            AddressingMode::Absolute
+            | AddressingMode::AbsoluteX
+            | AddressingMode::AbsoluteY
+            | AddressingMode::Indirect => self.arg_to_le_u16(),
+            _ => None
         }
+    }
 
-        ret
+    /// Replace the referenced address with a label in the operand
+    pub fn set_arg_label(&mut self, label: &str) {
+        self.fmt_arg = match (&self.opcode, self.table.map(|entry| entry.kind)) {
+            (Some(opcode), _) => opcode.mode.fmt_arg_with_reg(label),
+            (None, Some(TableKind::Rts)) => format!("{}-1", label),
+            (None, Some(TableKind::Jump)) => label.to_string(),
+            (None, None) => return
+        };
     }
-}
 
-impl PartialOrd for Line {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    /// Split a data line, `self` keeps the `at` first bytes
+    pub fn split_off(&mut self, at: usize) -> Line {
+        let mut ret = Line::data(
+            self.address.wrapping_add(at as u16),
+            &self.bytes.split_off(at)
+        );
+
+        ret.note = self.note.clone();
+        self.table = None;
+
+        self.fmt();
+        ret.fmt();
+
+        ret
     }
-}
 
-impl PartialEq for Line {
-    fn eq(&self, other: &Self) -> bool {
-        self.cmp(other) == Ordering::Equal
+    /// Word stored in a table entry
+    fn table_word(&self) -> Option<u16> {
+        let entry = self.table?;
+        let (first, second) = (*self.bytes.first()?, *self.bytes.get(1)?);
+
+        match entry.big_endian {
+            true => Some(u16_from_mem(second, first)),
+            false => Some(u16_from_mem(first, second))
+        }
     }
-}
 
-impl fmt::Display for Line {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let label = unwrap_str(&self.label, "", ":\n");
-        let comment = unwrap_str(&self.comment, "", "");
-
-        write!(f, "{}{} {}{}\n",
-            label,
-            self.opcode.mnemonic,
-            self.fmt_arg,
-            comment
-        )
+    fn arg_to_le_u16(&self) -> Option<u16> {
+        let arg_bytes = self.bytes[1..].to_vec();
+
+        if arg_bytes.len() < 2 {
+            return None;
+        }
+
+        Some(u16_from_mem(arg_bytes[0], arg_bytes[1]))
     }
 }
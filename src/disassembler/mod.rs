@@ -1,7 +1,43 @@
 /// Nes header
 pub mod header;
 
+/// Nes header typed values (NES 2.0, iNES 1.0, archaic iNES)
+pub mod header_info;
+
+/// PRG ROM banks and their CPU addresses
+pub mod bank;
+
+/// Nes ROM validation
+pub mod validation;
+
+/// Control flow tracing from the CPU vectors
+pub mod trace;
+
+/// Jump tables of the dispatch routines (`rts` trick, `jmp (ptr)`)
+pub mod table;
+
+/// Labels of the code and data addresses
+pub mod label;
+
+/// Symbol files of the debuggers and assemblers
+pub mod symbol;
+
+/// Code/Data Logger files of the emulators
+pub mod cdl;
+
+/// Cross-references of the code and data addresses
+pub mod xref;
+
+/// Call graph and control flow graphs
+pub mod graph;
+
+/// Assembler syntaxes of the disassembly output
+pub mod dialect;
+
 /// Nes disassembler
 pub mod disassembler;
 
+/// Reassembly of the disassembly
+pub mod roundtrip;
+
 mod line;
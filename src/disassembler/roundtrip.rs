@@ -0,0 +1,105 @@
+use crate::{
+    assembler::assembler::Assembler,
+    disassembler::{
+        dialect::Asm6,
+        disassembler::NesDisassembler
+    },
+    utils::{
+        error::NesError,
+        util::path_to_name
+    }
+};
+
+/// A byte of the ROM file that the reassembly does not match
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Difference {
+    pub offset: usize,
+    /// `None` past the end of the ROM file
+    pub expected: Option<u8>,
+    /// `None` past the end of the reassembled file
+    pub actual: Option<u8>
+}
+
+/// Disassembly reassembled with the built-in assembler
+#[derive(Debug, Clone)]
+pub struct RoundTrip {
+    pub source: String,
+    pub bytes: Vec<u8>,
+    pub differences: Vec<Difference>
+}
+
+impl RoundTrip {
+    /// Return true if the reassembly is byte-exact
+    pub fn is_exact(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Bytes differing between two files
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::roundtrip::{diff, Difference};
+///
+/// let differences = diff(&[0x01, 0x02], &[0x01, 0x03, 0x04]);
+///
+/// assert_eq!(differences, [
+///     Difference { offset: 1, expected: Some(0x02), actual: Some(0x03) },
+///     Difference { offset: 2, expected: None, actual: Some(0x04) }
+/// ]);
+/// ```
+pub fn diff(expected: &[u8], actual: &[u8]) -> Vec<Difference> {
+    (0..expected.len().max(actual.len()))
+        .map(|offset| Difference {
+            offset,
+            expected: expected.get(offset).copied(),
+            actual: actual.get(offset).copied()
+        })
+        .filter(|difference| difference.expected != difference.actual)
+        .collect()
+}
+
+impl NesDisassembler<'_> {
+    /// Reassemble the disassembly and compare it with the ROM file.
+    ///
+    /// The built-in assembler reads the asm6 syntax, the source is written
+    /// in asm6f whatever the dialect set with `set_dialect`.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```no_run
+    /// use nes_utils::disassembler::disassembler::NesDisassembler;
+    /// use nes_utils::models::nesutil_model::Util;
+    /// use nes_utils::rom::rom::NesRom;
+    ///
+    /// let rom = NesRom::from_file("games/game.nes").unwrap();
+    ///
+    /// let mut d = NesDisassembler::new(&rom);
+    /// d.run().unwrap();
+    ///
+    /// let round_trip = d.asm6_round_trip().unwrap();
+    /// assert!(round_trip.is_exact());
+    /// ```
+    pub fn asm6_round_trip(&self) -> Result<RoundTrip, NesError> {
+        let rom = self.rom();
+        let chr_path = format!("{}.chr", path_to_name(rom.path()));
+        let chr = (!rom.chr_rom().is_empty()).then_some(chr_path.as_str());
+
+        let source = self.source(&Asm6::new(true), chr);
+        let bytes = Assembler::new()
+            .add_file(&chr_path, rom.chr_rom())
+            .assemble(&source)?;
+        let differences = diff(rom.bytes(), &bytes);
+
+        Ok(RoundTrip {
+            source,
+            bytes,
+            differences
+        })
+    }
+}
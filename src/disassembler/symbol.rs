@@ -0,0 +1,446 @@
+use std::{
+    collections::HashMap,
+    fs
+};
+
+use crate::{
+    disassembler::{
+        bank::{
+            resolve_bank,
+            PrgBank
+        },
+        header::NesHeader
+    },
+    utils::{
+        error::NesError,
+        opcode::get_nes_opcode,
+        util::json_string
+    }
+};
+
+/// Size of the PRG banks of the FCEUX `.nl` files
+const NL_BANK_SIZE: usize = 0x4000;
+
+/// Where a symbol points to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolLocation {
+    /// CPU address, the PRG bank is resolved like an operand
+    Cpu(u16),
+    /// Offset in the PRG ROM
+    Prg(usize)
+}
+
+/// A name from a symbol file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub location: SymbolLocation,
+    pub name: String,
+    pub comment: Option<String>
+}
+
+impl Symbol {
+    /// The name is made of valid assembler characters and is not
+    /// a register or a mnemonic
+    pub fn new(location: SymbolLocation, name: &str, comment: Option<&str>) -> Self {
+        let mut name: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            name.insert(0, '_');
+        }
+        if is_reserved(&name) {
+            name.push('_');
+        }
+
+        let comment = comment
+            .map(str::trim)
+            .filter(|comment| !comment.is_empty())
+            .map(str::to_string);
+
+        Self {
+            location,
+            name,
+            comment
+        }
+    }
+
+    /// Bank and CPU address of a symbol in the PRG ROM
+    pub fn prg_address<'b>(&self, banks: &'b [PrgBank]) -> Option<(&'b PrgBank, u16)> {
+        match self.location {
+            SymbolLocation::Prg(offset) => {
+                let bank = banks.iter().find(|bank| bank.contains_offset(offset))?;
+
+                Some((bank, bank.address_of(offset)))
+            },
+            SymbolLocation::Cpu(address) => Some((resolve_bank(banks, None, address)?, address))
+        }
+    }
+
+    /// Comment on a single line
+    fn inline_comment(&self) -> String {
+        self.comment
+            .as_deref()
+            .unwrap_or("")
+            .replace('\n', " ")
+    }
+}
+
+/// Symbols loaded from the FCEUX, Mesen and ca65 files
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::symbol::{Symbols, SymbolLocation};
+///
+/// // Mesen label file
+/// let symbols = Symbols::from_mlb("P:3FFA:nmi_handler:Vertical blank\nR:0010:frame_counter:");
+///
+/// let symbol = symbols.iter().next().unwrap();
+///
+/// assert_eq!(symbols.len(), 2);
+/// assert_eq!(symbol.location, SymbolLocation::Prg(0x3ffa));
+/// assert_eq!(symbol.name, "nmi_handler");
+/// assert_eq!(symbol.comment.as_deref(), Some("Vertical blank"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Symbols {
+    symbols: Vec<Symbol>
+}
+
+impl Symbols {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a symbol file from its extension (`.nl`, `.mlb`, `.lbl` or `.dbg`).
+    ///
+    /// The FCEUX files are named after the bank (`game.nes.0.nl`, in hexadecimal)
+    /// or the RAM (`game.nes.ram.nl`). Other files have no symbols.
+    pub fn from_file(path: &str) -> Result<Self, NesError> {
+        let text = fs::read_to_string(path)
+            .map_err(|source| NesError::FileInvalid {
+                path: path.to_string(),
+                source
+            })?;
+        let mut parts = path.rsplit('.');
+        let extension = parts.next().unwrap_or("").to_ascii_lowercase();
+
+        let ret = match extension.as_str() {
+            "nl" => {
+                let bank = match (parts.next(), parts.next()) {
+                    (Some(bank), Some(rom)) if rom.eq_ignore_ascii_case("nes") => {
+                        usize::from_str_radix(bank, 16).ok()
+                    },
+                    _ => None
+                };
+
+                Self::from_nl(&text, bank)
+            },
+            "mlb" => Self::from_mlb(&text),
+            "lbl" => Self::from_lbl(&text),
+            "dbg" => Self::from_dbg(&text),
+            _ => Self::new()
+        };
+
+        Ok(ret)
+    }
+
+    /// FCEUX `$C000#name#comment` lines of a 16 KiB bank, `None` for the RAM file
+    pub fn from_nl(text: &str, bank: Option<usize>) -> Self {
+        let mut ret = Self::new();
+
+        for line in text.lines() {
+            let mut fields = line.trim().splitn(3, '#');
+            let (address, name) = match (fields.next(), fields.next()) {
+                (Some(address), Some(name)) => (address, name),
+                _ => continue
+            };
+            // `$0300/10` names a range
+            let address = address
+                .trim_start_matches('$')
+                .split('/')
+                .next()
+                .and_then(|address| u16::from_str_radix(address, 16).ok());
+
+            let location = match (address, bank) {
+                (Some(address), Some(bank)) if address >= 0x8000 => {
+                    SymbolLocation::Prg(bank * NL_BANK_SIZE + (address as usize % NL_BANK_SIZE))
+                },
+                (Some(address), _) => SymbolLocation::Cpu(address),
+                (None, _) => continue
+            };
+
+            ret.push(location, name, fields.next());
+        }
+
+        ret
+    }
+
+    /// Mesen `type:address:name:comment` lines, Mesen 2 types included
+    pub fn from_mlb(text: &str) -> Self {
+        let mut ret = Self::new();
+
+        for line in text.lines() {
+            let mut fields = line.trim().splitn(4, ':');
+            let (kind, address, name) = match (fields.next(), fields.next(), fields.next()) {
+                (Some(kind), Some(address), Some(name)) => (kind, address, name),
+                _ => continue
+            };
+            // `0300-030F` names a range
+            let address = match address
+                .split('-')
+                .next()
+                .and_then(|address| usize::from_str_radix(address, 16).ok())
+            {
+                Some(address) => address,
+                None => continue
+            };
+
+            let location = match kind {
+                "P" | "NesPrgRom" => SymbolLocation::Prg(address),
+                "R" | "NesInternalRam" | "G" | "NesMemory" | "Register" => {
+                    SymbolLocation::Cpu(address as u16)
+                },
+                // Mapped from $6000
+                "S" | "W" | "NesSaveRam" | "NesWorkRam" => {
+                    SymbolLocation::Cpu(0x6000u16.wrapping_add(address as u16))
+                },
+                _ => continue
+            };
+            let comment = fields.next().map(|comment| comment.replace("\\n", "\n"));
+
+            ret.push(location, name, comment.as_deref());
+        }
+
+        ret
+    }
+
+    /// ld65 `-Ln` label file, `al 00C000 .name` lines
+    pub fn from_lbl(text: &str) -> Self {
+        let mut ret = Self::new();
+
+        for line in text.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+
+            if let ["al", address, name] = fields[..] {
+                if let Ok(address) = u32::from_str_radix(address, 16) {
+                    let name = name.trim_start_matches('.');
+
+                    ret.push(SymbolLocation::Cpu(address as u16), name, None);
+                }
+            }
+        }
+
+        ret
+    }
+
+    /// ld65 `--dbgfile` debug information, the labels of the segments
+    /// written to the NES file are in their PRG bank
+    pub fn from_dbg(text: &str) -> Self {
+        let mut ret = Self::new();
+        // Segment id to CPU start address and offset in the output file
+        let mut segments: HashMap<String, (usize, Option<usize>)> = HashMap::new();
+        let mut labels = Vec::new();
+
+        for line in text.lines() {
+            let (kind, attributes) = match line.split_once(char::is_whitespace) {
+                Some((kind, attributes)) => (kind, dbg_attributes(attributes)),
+                None => continue
+            };
+            let number = |key: &str| attributes.get(key).and_then(|value| dbg_number(value));
+
+            match kind {
+                "seg" => {
+                    if let (Some(id), Some(start)) = (attributes.get("id"), number("start")) {
+                        segments.insert(id.to_string(), (start, number("ooffs")));
+                    }
+                },
+                "sym" if attributes.get("type") == Some(&"lab") => {
+                    if let (Some(name), Some(value)) = (attributes.get("name"), number("val")) {
+                        let segment = attributes.get("seg").map(|seg| seg.to_string());
+
+                        labels.push((name.trim_matches('"').to_string(), value, segment));
+                    }
+                },
+                _ => {}
+            };
+        }
+
+        for (name, value, segment) in labels {
+            let file_offset = segment
+                .and_then(|segment| segments.get(&segment))
+                .and_then(|(start, ooffs)| Some(ooffs.as_ref()? + value.checked_sub(*start)?));
+
+            let location = match file_offset {
+                Some(offset) if offset >= NesHeader::HEADER_SIZE && value >= 0x8000 => {
+                    SymbolLocation::Prg(offset - NesHeader::HEADER_SIZE)
+                },
+                _ => SymbolLocation::Cpu(value as u16)
+            };
+
+            ret.push(location, &name, None);
+        }
+
+        ret
+    }
+
+    /// FCEUX lines of a 16 KiB bank, `None` for the RAM file
+    pub fn to_nl(&self, banks: &[PrgBank], bank: Option<usize>) -> String {
+        let mut ret = String::new();
+
+        for symbol in &self.symbols {
+            let address = match (symbol.location, bank) {
+                (SymbolLocation::Prg(offset), Some(bank)) if offset / NL_BANK_SIZE == bank => {
+                    symbol.prg_address(banks).map(|(_, address)| address)
+                },
+                (SymbolLocation::Cpu(address), None) if address < 0x8000 => Some(address),
+                _ => None
+            };
+
+            if let Some(address) = address {
+                ret.push_str(&format!(
+                    "${:04X}#{}#{}\n",
+                    address, symbol.name, symbol.inline_comment()
+                ));
+            }
+        }
+
+        ret
+    }
+
+    /// 16 KiB banks of the FCEUX files with symbols
+    pub fn nl_banks(&self) -> Vec<usize> {
+        let mut ret: Vec<usize> = self.symbols
+            .iter()
+            .filter_map(|symbol| match symbol.location {
+                SymbolLocation::Prg(offset) => Some(offset / NL_BANK_SIZE),
+                SymbolLocation::Cpu(_) => None
+            })
+            .collect();
+
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+
+    /// Mesen lines, the PRG ROM addresses are offsets
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use nes_utils::disassembler::symbol::Symbols;
+    ///
+    /// let text = "P:0010:start:\nR:0020:counter:Frames\n";
+    ///
+    /// assert_eq!(Symbols::from_mlb(text).to_mlb(), text);
+    /// ```
+    pub fn to_mlb(&self) -> String {
+        let mut ret = String::new();
+
+        for symbol in &self.symbols {
+            let (kind, address) = match symbol.location {
+                SymbolLocation::Prg(offset) => ("P", offset),
+                SymbolLocation::Cpu(address @ 0x0000..=0x1fff) => ("R", address as usize & 0x07ff),
+                SymbolLocation::Cpu(address @ 0x6000..=0x7fff) => ("W", address as usize - 0x6000),
+                SymbolLocation::Cpu(address @ 0x2000..=0x5fff) => ("G", address as usize),
+                SymbolLocation::Cpu(_) => continue
+            };
+            let comment = symbol.comment
+                .as_deref()
+                .unwrap_or("")
+                .replace('\n', "\\n");
+
+            ret.push_str(&format!("{}:{:04X}:{}:{}\n", kind, address, symbol.name, comment));
+        }
+
+        ret
+    }
+
+    /// JSON array of the symbols, the PRG ROM ones have their bank and offset
+    pub fn to_json(&self, banks: &[PrgBank]) -> String {
+        let mut entries = Vec::new();
+
+        for symbol in &self.symbols {
+            let mut fields = vec![format!("\"name\": {}", json_string(&symbol.name))];
+
+            match (symbol.location, symbol.prg_address(banks)) {
+                (_, Some((bank, address))) => {
+                    fields.push(format!("\"address\": {}", address));
+                    fields.push(format!("\"bank\": {}", bank.index));
+                    fields.push(format!("\"offset\": {}", bank.offset_of(address).unwrap_or(0)));
+                },
+                (SymbolLocation::Cpu(address), None) => {
+                    fields.push(format!("\"address\": {}", address));
+                },
+                (SymbolLocation::Prg(_), None) => continue
+            };
+
+            if let Some(comment) = &symbol.comment {
+                fields.push(format!("\"comment\": {}", json_string(comment)));
+            }
+
+            entries.push(format!("  {{ {} }}", fields.join(", ")));
+        }
+
+        if entries.is_empty() {
+            return String::from("[]\n");
+        }
+
+        format!("[\n{}\n]\n", entries.join(",\n"))
+    }
+
+    /// Add a symbol, ignored without a name
+    pub fn push(&mut self, location: SymbolLocation, name: &str, comment: Option<&str>) {
+        let name = name.trim();
+
+        if !name.is_empty() {
+            self.symbols.push(Symbol::new(location, name, comment));
+        }
+    }
+
+    pub fn extend(&mut self, other: Symbols) {
+        self.symbols.extend(other.symbols);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Symbol> {
+        self.symbols.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+}
+
+fn is_reserved(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+
+    ["a", "x", "y"].contains(&name.as_str())
+        || (0..=255u8).any(|code| get_nes_opcode(&code).is_some_and(|opcode| opcode.mnemonic == name))
+}
+
+/// `key=value,key="value"` attributes of a debug information line
+fn dbg_attributes(text: &str) -> HashMap<&str, &str> {
+    text
+        .trim()
+        .split(',')
+        .filter_map(|attribute| attribute.split_once('='))
+        .collect()
+}
+
+/// `0x8000` or `32768`
+fn dbg_number(value: &str) -> Option<usize> {
+    match value.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok()
+    }
+}
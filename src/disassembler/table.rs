@@ -0,0 +1,342 @@
+use std::collections::HashSet;
+
+use crate::{
+    disassembler::{
+        bank::{
+            resolve_bank,
+            PrgBank
+        },
+        trace::ByteKind
+    },
+    utils::{
+        addressing::AddressingMode,
+        opcode::{
+            OpCode,
+            OpCodeSet
+        },
+        util::u16_from_mem
+    }
+};
+
+/// Maximum number of entries read from a table
+const MAX_ENTRIES: usize = 128;
+
+/// Instructions searched before a `jmp (ptr)` for the pointer writes
+const POINTER_SEARCH: usize = 8;
+
+/// How a table entry stores its code address
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+    /// Address minus one, pushed on the stack and returned to with `rts`
+    Rts,
+    /// Address copied to the pointer of a `jmp (ptr)`
+    Jump
+}
+
+impl TableKind {
+    /// Subtracted from the code addresses in the entries
+    pub fn bias(&self) -> u16 {
+        match self {
+            Self::Rts => 1,
+            Self::Jump => 0
+        }
+    }
+}
+
+/// Entry of a table of words, as written in the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableEntry {
+    pub kind: TableKind,
+    /// High byte first, not a `.word`
+    pub big_endian: bool
+}
+
+/// Table of code addresses read by a dispatch routine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpTable {
+    /// PRG bank of the dispatch code, the table and the targets are seen from it
+    pub bank: usize,
+    /// CPU address of the `rts` or `jmp (ptr)`
+    pub dispatch: u16,
+    pub kind: TableKind,
+    /// CPU address of the low bytes
+    pub low: u16,
+    /// CPU address of the high bytes, `low + 1` for a table of words,
+    /// `low - 1` if the words are stored high byte first
+    pub high: u16,
+    /// Code addresses of the entries
+    pub targets: Vec<u16>
+}
+
+impl JumpTable {
+    /// Return true if the words are stored high byte first
+    pub fn is_big_endian(&self) -> bool {
+        self.high.wrapping_add(1) == self.low
+    }
+
+    /// Return true if the low and high bytes are stored in two tables
+    pub fn is_split(&self) -> bool {
+        self.high != self.low.wrapping_add(1) && !self.is_big_endian()
+    }
+
+    /// CPU address of the first byte of an entry
+    pub fn entry(&self, n: usize) -> u16 {
+        let start = if self.is_big_endian() { self.high } else { self.low };
+
+        start.wrapping_add(self.step() * n as u16)
+    }
+
+    /// How an entry of a table of words is written, `None` if split
+    pub fn entry_format(&self) -> Option<TableEntry> {
+        (!self.is_split()).then_some(TableEntry {
+            kind: self.kind,
+            big_endian: self.is_big_endian()
+        })
+    }
+
+    /// Distance between two entries
+    fn step(&self) -> u16 {
+        if self.is_split() { 1 } else { 2 }
+    }
+}
+
+/// Decoded instruction of the traced code
+struct Instruction {
+    address: u16,
+    opcode: OpCode,
+    /// Memory address of the operand
+    operand: Option<u16>
+}
+
+impl Instruction {
+    /// Absolute indexed `lda`, reading a table
+    fn table_read(&self) -> Option<(u16, AddressingMode)> {
+        match (self.opcode.mnemonic, self.opcode.mode) {
+            ("lda", AddressingMode::AbsoluteX | AddressingMode::AbsoluteY) => {
+                self.operand.map(|address| (address, self.opcode.mode))
+            },
+            _ => None
+        }
+    }
+
+    /// `sta` to a zero page or absolute address
+    fn store(&self) -> Option<u16> {
+        match (self.opcode.mnemonic, self.opcode.mode) {
+            ("sta", AddressingMode::ZeroPage | AddressingMode::Absolute) => self.operand,
+            _ => None
+        }
+    }
+}
+
+/// Find the tables dispatched by the traced code, the low and high bytes
+/// are read with the same index register:
+///
+/// - `lda high,y; pha; lda low,y; pha; rts`, the words may be stored
+///   high byte first (`lda table,y; pha; lda table+1,y; pha; rts`)
+/// - `lda low,y; sta ptr; lda high,y; sta ptr+1; jmp (ptr)`
+///
+/// The entries are read until one is not data or does not point to
+/// the start of a valid instruction, a guess over bytes that are not
+/// a table is dropped when its first entry is invalid.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::bank::prg_banks;
+/// use nes_utils::disassembler::table::{find_tables, TableKind};
+/// use nes_utils::disassembler::trace::Tracer;
+/// use nes_utils::rom::rom::NesRom;
+/// use nes_utils::utils::opcode::OpCodeSet;
+///
+/// // NROM-128, reset: lda $c00a,y; pha; lda $c009,y; pha; rts
+/// // then a table of one word, $c00b - 1
+/// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00];
+/// mem.resize(16, 0x00);
+/// mem.resize(16 + 0x4000, 0xff);
+/// mem[16..30].copy_from_slice(&[
+///     0xb9, 0x0a, 0xc0, 0x48, 0xb9, 0x09, 0xc0, 0x48, 0x60,
+///     0x0a, 0xc0,
+///     0x4c, 0x0b, 0xc0
+/// ]);
+/// mem[16 + 0x3ffc..16 + 0x3ffe].copy_from_slice(&[0x00, 0xc0]);
+///
+/// let rom = NesRom::new("game.nes", &mem).unwrap();
+/// let banks = prg_banks(&rom);
+///
+/// let mut tracer = Tracer::new(rom.prg_rom(), &banks);
+/// tracer.trace_vectors();
+///
+/// let tables = find_tables(rom.prg_rom(), &banks, tracer.kinds(), OpCodeSet::Official);
+///
+/// assert_eq!(tables[0].kind, TableKind::Rts);
+/// assert_eq!(tables[0].low, 0xc009);
+/// assert_eq!(tables[0].targets, vec![0xc00b]);
+/// ```
+pub fn find_tables(
+    prg: &[u8],
+    banks: &[PrgBank],
+    kinds: &[ByteKind],
+    opcodes: OpCodeSet
+) -> Vec<JumpTable> {
+    let mut ret = Vec::new();
+
+    for bank in banks {
+        let instructions: Vec<Instruction> = (bank.offset..bank.offset + bank.size)
+            .filter(|offset| kinds[*offset] == ByteKind::Code)
+            .filter_map(|offset| {
+                let opcode = opcodes.decode(&prg[offset])?;
+                let bytes = prg.get(offset..offset + opcode.len as usize)?;
+                let operand = match bytes.len() {
+                    2 => Some(bytes[1] as u16),
+                    3 => Some(u16_from_mem(bytes[1], bytes[2])),
+                    _ => None
+                };
+
+                Some(Instruction { address: bank.address_of(offset), opcode, operand })
+            })
+            .collect();
+
+        for (n, instruction) in instructions.iter().enumerate() {
+            // Instructions executed in sequence before the dispatch
+            let start = (0..n)
+                .rev()
+                .take_while(|i| {
+                    let previous = &instructions[*i];
+
+                    previous.address.wrapping_add(previous.opcode.len as u16)
+                        == instructions[*i + 1].address
+                })
+                .last()
+                .unwrap_or(n);
+            let previous = &instructions[start..n];
+
+            let sources = match (instruction.opcode.mnemonic, instruction.opcode.mode) {
+                ("rts", _) => rts_sources(previous).map(|sources| (TableKind::Rts, sources)),
+                ("jmp", AddressingMode::Indirect) => instruction.operand
+                    .and_then(|pointer| jump_sources(previous, pointer))
+                    .map(|sources| (TableKind::Jump, sources)),
+                _ => None
+            };
+
+            if let Some((kind, (low, high))) = sources {
+                let mut table = JumpTable {
+                    bank: bank.index,
+                    dispatch: instruction.address,
+                    kind,
+                    low,
+                    high,
+                    targets: Vec::new()
+                };
+
+                table.targets = read_targets(prg, banks, kinds, opcodes, &table);
+
+                if !table.targets.is_empty() {
+                    ret.push(table);
+                }
+            }
+        }
+    }
+
+    ret
+}
+
+/// `lda high,r; pha; lda low,r; pha` before the `rts`
+fn rts_sources(previous: &[Instruction]) -> Option<(u16, u16)> {
+    let [high, push_high, low, push_low] = previous.get(previous.len().checked_sub(4)?..)? else {
+        return None;
+    };
+
+    let (high, high_mode) = high.table_read()?;
+    let (low, low_mode) = low.table_read()?;
+
+    let pushes = push_high.opcode.mnemonic == "pha" && push_low.opcode.mnemonic == "pha";
+
+    (pushes && high_mode == low_mode).then_some((low, high))
+}
+
+/// `lda low,r; sta ptr` and `lda high,r; sta ptr+1` before the `jmp (ptr)`
+fn jump_sources(previous: &[Instruction], pointer: u16) -> Option<(u16, u16)> {
+    let previous = &previous[previous.len().saturating_sub(POINTER_SEARCH)..];
+
+    // Last table read stored to an address
+    let source = |address: u16| {
+        previous
+            .windows(2)
+            .rev()
+            .find(|pair| pair[1].store() == Some(address))
+            .and_then(|pair| pair[0].table_read())
+    };
+
+    let (low, low_mode) = source(pointer)?;
+    let (high, high_mode) = source(pointer.wrapping_add(1))?;
+
+    (low_mode == high_mode).then_some((low, high))
+}
+
+/// Code addresses of the entries, read until an invalid one
+fn read_targets(
+    prg: &[u8],
+    banks: &[PrgBank],
+    kinds: &[ByteKind],
+    opcodes: OpCodeSet,
+    table: &JumpTable
+) -> Vec<u16> {
+    let offset_of = |address: u16| {
+        resolve_bank(banks, Some(table.bank), address)
+            .and_then(|bank| bank.offset_of(address))
+    };
+    // Offset of a whole instruction starting at the address, `brk` is
+    // padding and never dispatched to
+    let instruction = |address: u16| {
+        let bank = resolve_bank(banks, Some(table.bank), address)?;
+        let offset = bank.offset_of(address)?;
+        let opcode = opcodes.decode(&prg[offset])?;
+        let valid = kinds[offset] != ByteKind::Operand
+            && opcode.mnemonic != "brk"
+            && offset + opcode.len as usize <= bank.offset + bank.size;
+
+        valid.then_some(offset)
+    };
+    let mut ret = Vec::new();
+    let mut targets = HashSet::new();
+    let mut entries = HashSet::new();
+
+    for n in 0..MAX_ENTRIES {
+        let low = table.low.wrapping_add(table.step() * n as u16);
+        let high = table.high.wrapping_add(table.step() * n as u16);
+
+        // Split tables stop at each other
+        if n > 0 && table.is_split() && (low == table.high || high == table.low) {
+            break;
+        }
+
+        let (low, high) = match (offset_of(low), offset_of(high)) {
+            (Some(low), Some(high)) => (low, high),
+            _ => break
+        };
+
+        // Entries are data, not the code of the previous entries
+        if kinds[low] != ByteKind::Data
+            || kinds[high] != ByteKind::Data
+            || targets.contains(&low)
+            || targets.contains(&high) {
+            break;
+        }
+
+        entries.extend([low, high]);
+
+        // Bytes pointing into themselves are not a table
+        let target = u16_from_mem(prg[low], prg[high]).wrapping_add(table.kind.bias());
+        let offset = match instruction(target) {
+            Some(offset) if !entries.contains(&offset) => offset,
+            _ => break
+        };
+
+        targets.insert(offset);
+        ret.push(target);
+    }
+
+    ret
+}
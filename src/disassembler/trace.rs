@@ -0,0 +1,210 @@
+use crate::{
+    disassembler::bank::{
+        resolve_bank,
+        PrgBank
+    },
+    utils::{
+        addressing::{
+            relative_target,
+            AddressingMode
+        },
+        opcode::OpCodeSet,
+        util::u16_from_mem
+    }
+};
+
+/// CPU vectors, stored in the last 6 bytes of the PRG ROM
+pub const VECTORS: [(&str, u16); 3] = [
+    ("nmi", 0xfffa),
+    ("reset", 0xfffc),
+    ("irq", 0xfffe)
+];
+
+/// Addresses stored in the CPU vectors at the end of the PRG ROM
+pub fn vectors(prg: &[u8]) -> Vec<(&'static str, u16)> {
+    let start = match prg.len().checked_sub(2 * VECTORS.len()) {
+        Some(start) => start,
+        None => return Vec::new()
+    };
+
+    VECTORS
+        .iter()
+        .enumerate()
+        .map(|(n, (name, _))| {
+            let offset = start + 2 * n;
+
+            (*name, u16_from_mem(prg[offset], prg[offset + 1]))
+        })
+        .collect()
+}
+
+/// What a PRG ROM byte has been identified as
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteKind {
+    /// Not reached by the control flow
+    Data,
+    /// First byte of an instruction
+    Code,
+    /// Operand of an instruction
+    Operand
+}
+
+/// A code address to trace from, `bank` is needed for switchable banks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EntryPoint {
+    pub address: u16,
+    pub bank: Option<usize>
+}
+
+/// Recursive descent over the PRG ROM, following `jsr`, `jmp` and branches
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::bank::prg_banks;
+/// use nes_utils::disassembler::trace::{ByteKind, Tracer};
+/// use nes_utils::rom::rom::NesRom;
+///
+/// // NROM-128, reset: jmp $c000
+/// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x00];
+/// mem.resize(16, 0x00);
+/// mem.resize(16 + 0x4000, 0xff);
+/// mem[16..19].copy_from_slice(&[0x4c, 0x00, 0xc0]);
+/// mem[16 + 0x3ffc..16 + 0x3ffe].copy_from_slice(&[0x00, 0xc0]);
+///
+/// let rom = NesRom::new("game.nes", &mem).unwrap();
+/// let banks = prg_banks(&rom);
+///
+/// let mut tracer = Tracer::new(rom.prg_rom(), &banks);
+/// tracer.trace_vectors();
+///
+/// assert_eq!(tracer.kinds()[0], ByteKind::Code);
+/// assert_eq!(tracer.kinds()[1], ByteKind::Operand);
+/// assert_eq!(tracer.kinds()[3], ByteKind::Data);
+/// ```
+pub struct Tracer<'a> {
+    prg: &'a [u8],
+    banks: &'a [PrgBank],
+    opcodes: OpCodeSet,
+    kinds: Vec<ByteKind>,
+    /// Bytes known as data, never decoded
+    data: Vec<bool>
+}
+
+impl<'a> Tracer<'a> {
+    pub fn new(prg: &'a [u8], banks: &'a [PrgBank]) -> Self {
+        Self {
+            prg,
+            banks,
+            opcodes: OpCodeSet::default(),
+            kinds: vec![ByteKind::Data; prg.len()],
+            data: vec![false; prg.len()]
+        }
+    }
+
+    /// Opcodes followed by the tracing, it stops on the other ones
+    pub fn set_opcodes(&mut self, opcodes: OpCodeSet) -> &mut Self {
+        self.opcodes = opcodes;
+        self
+    }
+
+    /// Mark a PRG ROM byte as data, the tracing stops before it
+    pub fn set_data(&mut self, offset: usize) -> &mut Self {
+        if let Some(data) = self.data.get_mut(offset) {
+            *data = true;
+        }
+        self
+    }
+
+    pub fn kinds(&self) -> &[ByteKind] {
+        &self.kinds
+    }
+
+    /// Bank holding the CPU vectors
+    pub fn vector_bank(&self) -> Option<&PrgBank> {
+        self.banks.last()
+    }
+
+    /// Addresses stored in the CPU vectors
+    pub fn vectors(&self) -> Vec<(&'static str, u16)> {
+        vectors(self.prg)
+    }
+
+    /// PRG bank and offset of a CPU address seen from the bank `from`
+    pub fn resolve(&self, from: Option<usize>, address: u16) -> Option<(usize, usize)> {
+        let bank = resolve_bank(self.banks, from, address)?;
+
+        Some((bank.index, bank.offset_of(address)?))
+    }
+
+    /// Trace the code reached from the NMI, reset and IRQ vectors
+    pub fn trace_vectors(&mut self) {
+        let from = self.vector_bank().map(|bank| bank.index);
+
+        for (_, address) in self.vectors() {
+            self.trace(EntryPoint { address, bank: from });
+        }
+    }
+
+    /// Trace the code reached from an entry point
+    pub fn trace(&mut self, entry: EntryPoint) {
+        let mut pending = vec![entry];
+
+        while let Some(entry) = pending.pop() {
+            if let Some((bank, offset)) = self.resolve(entry.bank, entry.address) {
+                self.trace_block(bank, offset, &mut pending);
+            }
+        }
+    }
+
+    /// Decode instructions until the control flow leaves the block
+    fn trace_block(&mut self, bank: usize, mut offset: usize, pending: &mut Vec<EntryPoint>) {
+        let bank = self.banks[bank];
+
+        while bank.contains_offset(offset) && self.kinds[offset] == ByteKind::Data {
+            let opcode = match self.opcodes.decode(&self.prg[offset]) {
+                Some(opcode) => opcode,
+                None => return
+            };
+            let len = opcode.len as usize;
+            let end = offset + len;
+
+            // Straddling the bank end, overlapping decoded bytes or known data
+            if !bank.contains_offset(end - 1)
+                || self.kinds[offset + 1..end].iter().any(|kind| *kind != ByteKind::Data)
+                || self.data[offset..end].iter().any(|data| *data) {
+                return;
+            }
+
+            self.kinds[offset] = ByteKind::Code;
+            self.kinds[offset + 1..end].fill(ByteKind::Operand);
+
+            let address = bank.address_of(offset);
+            let bytes = &self.prg[offset..end];
+
+            match (opcode.mnemonic, opcode.mode) {
+                ("jsr", _) | ("jmp", AddressingMode::Absolute) => {
+                    pending.push(EntryPoint {
+                        address: u16_from_mem(bytes[1], bytes[2]),
+                        bank: Some(bank.index)
+                    });
+                },
+                (_, AddressingMode::Relative) => {
+                    pending.push(EntryPoint {
+                        address: relative_target(address, bytes[1]),
+                        bank: Some(bank.index)
+                    });
+                },
+                _ => {}
+            };
+
+            if matches!(opcode.mnemonic, "jmp" | "rts" | "rti" | "brk" | "jam") {
+                return;
+            }
+
+            offset = end;
+        }
+    }
+}
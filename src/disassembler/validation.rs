@@ -0,0 +1,172 @@
+use std::fmt;
+
+use crate::{
+    disassembler::header_info::HeaderInfo,
+    disassembler::header::NesHeader,
+    utils::error::NesError
+};
+
+/// Issue found in a ROM file by `validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RomDiagnostic {
+    TruncatedHeader {
+        actual: usize
+    },
+    MissingTrainer {
+        offset: usize,
+        expected: usize,
+        actual: usize
+    },
+    TruncatedPrgRom {
+        offset: usize,
+        expected: usize,
+        actual: usize
+    },
+    TruncatedChrRom {
+        offset: usize,
+        expected: usize,
+        actual: usize
+    },
+    /// Extra bytes after the CHR ROM
+    TrailingData {
+        offset: usize,
+        size: usize
+    },
+    PrgRomNotPowerOfTwo(usize),
+    ChrRomNotPowerOfTwo(usize)
+}
+
+impl RomDiagnostic {
+    /// Return true if the ROM can't be read as described by its header
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            Self::TruncatedHeader { .. }
+                | Self::MissingTrainer { .. }
+                | Self::TruncatedPrgRom { .. }
+                | Self::TruncatedChrRom { .. }
+        )
+    }
+
+    /// Matching error for the fatal diagnostics
+    pub fn error(&self) -> Option<NesError> {
+        let (offset, expected, actual) = match *self {
+            Self::TruncatedHeader { actual } => (0, NesHeader::HEADER_SIZE, actual),
+            Self::MissingTrainer { offset, expected, actual }
+            | Self::TruncatedPrgRom { offset, expected, actual }
+            | Self::TruncatedChrRom { offset, expected, actual } => (offset, expected, actual),
+            _ => return None
+        };
+
+        Some(NesError::UnexpectedSize { offset, expected, actual })
+    }
+}
+
+impl fmt::Display for RomDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TruncatedHeader { actual } => write!(
+                f, "Truncated header (0x{:x} bytes)", actual
+            ),
+            Self::MissingTrainer { offset, expected, actual } => write!(
+                f, "Missing trainer bytes at 0x{:x} (0x{:x} / 0x{:x})",
+                offset, actual, expected
+            ),
+            Self::TruncatedPrgRom { offset, expected, actual } => write!(
+                f, "Truncated PRG ROM at 0x{:x} (0x{:x} / 0x{:x})",
+                offset, actual, expected
+            ),
+            Self::TruncatedChrRom { offset, expected, actual } => write!(
+                f, "Truncated CHR ROM at 0x{:x} (0x{:x} / 0x{:x})",
+                offset, actual, expected
+            ),
+            Self::TrailingData { offset, size } => write!(
+                f, "0x{:x} bytes of trailing data at 0x{:x}", size, offset
+            ),
+            Self::PrgRomNotPowerOfTwo(size) => write!(
+                f, "PRG ROM size 0x{:x} is not a power of two", size
+            ),
+            Self::ChrRomNotPowerOfTwo(size) => write!(
+                f, "CHR ROM size 0x{:x} is not a power of two", size
+            )
+        }
+    }
+}
+
+/// Build a diagnostic from (offset, expected, actual)
+type AreaDiagnostic = fn(usize, usize, usize) -> RomDiagnostic;
+
+/// Check an area of `expected` bytes at `offset`, returns its available size
+fn check_area(mem: &[u8], offset: usize, expected: usize) -> usize {
+    mem.len()
+        .saturating_sub(offset)
+        .min(expected)
+}
+
+/// Validate a whole ROM file against its header.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::disassembler::validation::{validate, RomDiagnostic};
+///
+/// let mut mem = vec![0x4e, 0x45, 0x53, 0x1a, 0x01, 0x01];
+/// mem.resize(16 + 0x4000, 0x00);
+///
+/// let diagnostics = validate(&mem);
+///
+/// assert_eq!(
+///     diagnostics,
+///     vec![RomDiagnostic::TruncatedChrRom {
+///         offset: 0x4010,
+///         expected: 0x2000,
+///         actual: 0
+///     }]
+/// );
+/// ```
+pub fn validate(mem: &[u8]) -> Vec<RomDiagnostic> {
+    let mut ret = Vec::new();
+
+    if mem.len() < NesHeader::HEADER_SIZE {
+        ret.push(RomDiagnostic::TruncatedHeader { actual: mem.len() });
+        return ret;
+    }
+
+    let info = HeaderInfo::decode(&mem[..NesHeader::HEADER_SIZE]);
+    let mut offset = NesHeader::HEADER_SIZE;
+    let trainer_size = if info.trainer { NesHeader::TRAINER_SIZE } else { 0 };
+    let areas: [(usize, AreaDiagnostic); 3] = [
+        (trainer_size, |offset, expected, actual| RomDiagnostic::MissingTrainer { offset, expected, actual }),
+        (info.prg_rom_size, |offset, expected, actual| RomDiagnostic::TruncatedPrgRom { offset, expected, actual }),
+        (info.chr_rom_size, |offset, expected, actual| RomDiagnostic::TruncatedChrRom { offset, expected, actual })
+    ];
+
+    for (expected, diagnostic) in areas {
+        let actual = check_area(mem, offset, expected);
+
+        if actual < expected {
+            ret.push(diagnostic(offset, expected, actual));
+        }
+
+        offset = offset.saturating_add(expected);
+    }
+
+    // Misc ROMs are stored after the CHR ROM
+    if offset < mem.len() && info.misc_rom_count == 0 {
+        ret.push(RomDiagnostic::TrailingData {
+            offset,
+            size: mem.len() - offset
+        });
+    }
+
+    if info.prg_rom_size > 0 && !info.prg_rom_size.is_power_of_two() {
+        ret.push(RomDiagnostic::PrgRomNotPowerOfTwo(info.prg_rom_size));
+    }
+    if info.chr_rom_size > 0 && !info.chr_rom_size.is_power_of_two() {
+        ret.push(RomDiagnostic::ChrRomNotPowerOfTwo(info.chr_rom_size));
+    }
+
+    ret
+}
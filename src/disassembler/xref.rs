@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use crate::utils::{
+    addressing::AddressingMode,
+    opcode::OpCode
+};
+
+/// How an instruction uses the address of its operand
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum XrefKind {
+    Call,
+    Jump,
+    Branch,
+    Read,
+    Write,
+    ReadModifyWrite
+}
+
+impl XrefKind {
+    /// Kind of the reference made by an instruction, `None` without address
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use nes_utils::disassembler::xref::XrefKind;
+    /// use nes_utils::utils::opcode::get_official_opcode;
+    ///
+    /// let kind = |code| XrefKind::of(&get_official_opcode(&code).unwrap());
+    ///
+    /// assert_eq!(kind(0x8d), Some(XrefKind::Write)); // sta $xxxx
+    /// assert_eq!(kind(0xee), Some(XrefKind::ReadModifyWrite)); // inc $xxxx
+    /// assert_eq!(kind(0x0a), None); // asl a
+    /// ```
+    pub fn of(opcode: &OpCode) -> Option<Self> {
+        let ret = match (opcode.mnemonic, opcode.mode) {
+            (_, AddressingMode::Implied)
+            | (_, AddressingMode::Accumulator)
+            | (_, AddressingMode::Immediate) => return None,
+            (_, AddressingMode::Relative) => Self::Branch,
+            // The pointer is read, the target is unknown
+            (_, AddressingMode::Indirect)
+            | (_, AddressingMode::IndexedIndirect)
+            | (_, AddressingMode::IndirectIndexed) => Self::Read,
+            ("jsr", _) => Self::Call,
+            ("jmp", _) => Self::Jump,
+            (mnemonic, _) if is_write(mnemonic) => Self::Write,
+            (mnemonic, _) if is_read_modify_write(mnemonic) => Self::ReadModifyWrite,
+            _ => Self::Read
+        };
+
+        Some(ret)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Call => "call",
+            Self::Jump => "jump",
+            Self::Branch => "branch",
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::ReadModifyWrite => "rmw"
+        }
+    }
+}
+
+fn is_write(mnemonic: &str) -> bool {
+    matches!(mnemonic, "sta" | "stx" | "sty" | "sax" | "sha" | "shx" | "shy" | "tas")
+}
+
+fn is_read_modify_write(mnemonic: &str) -> bool {
+    matches!(
+        mnemonic,
+        "asl" | "lsr" | "rol" | "ror" | "inc" | "dec"
+            | "slo" | "rla" | "sre" | "rra" | "dcp" | "isc"
+    )
+}
+
+/// An instruction referencing an address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Xref {
+    /// PRG bank of the instruction
+    pub bank: usize,
+    /// CPU address of the instruction
+    pub address: u16,
+    pub kind: XrefKind
+}
+
+/// Cross-references indexed by the referenced address, its PRG bank
+/// is `None` outside of the PRG ROM (RAM, registers)
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use nes_utils::disassembler::disassembler::NesDisassembler;
+/// use nes_utils::disassembler::xref::XrefKind;
+/// use nes_utils::models::nesutil_model::Util;
+/// use nes_utils::rom::rom::NesRom;
+///
+/// let rom = NesRom::from_file("games/game.nes").unwrap();
+///
+/// let mut d = NesDisassembler::new(&rom);
+/// d.run().unwrap();
+///
+/// // Who writes PPUADDR
+/// for xref in d.xrefs().get(None, 0x2006) {
+///     if xref.kind == XrefKind::Write {
+///         println!("${:04x}", xref.address);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Xrefs {
+    xrefs: BTreeMap<(Option<usize>, u16), Vec<Xref>>
+}
+
+impl Xrefs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a reference to `address` of the PRG bank `bank`
+    pub fn insert(&mut self, bank: Option<usize>, address: u16, xref: Xref) {
+        let xrefs = self.xrefs
+            .entry((bank, address))
+            .or_default();
+
+        if let Err(index) = xrefs.binary_search(&xref) {
+            xrefs.insert(index, xref);
+        }
+    }
+
+    /// References to an address, sorted by bank and address
+    pub fn get(&self, bank: Option<usize>, address: u16) -> &[Xref] {
+        self.xrefs
+            .get(&(bank, address))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Referenced addresses (bank and CPU address) and their references
+    pub fn iter(&self) -> impl Iterator<Item = (Option<usize>, u16, &[Xref])> {
+        self.xrefs
+            .iter()
+            .map(|((bank, address), xrefs)| (*bank, *address, xrefs.as_slice()))
+    }
+
+    /// Number of referenced addresses
+    pub fn len(&self) -> usize {
+        self.xrefs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.xrefs.is_empty()
+    }
+}
@@ -3,7 +3,8 @@ use crate::{
         NesUtil,
         Util,
         Save
-    }
+    },
+    utils::error::NesError
 };
 
 fn letter_to_bin(letter: char) -> Option<u8> {
@@ -32,9 +33,9 @@ fn letter_to_bin(letter: char) -> Option<u8> {
 
 type Cell = [char; 4];
 
-const VALUE_ORDER: &'static str = "12345678";
-const ADDRESS_ORDER: &'static str = "ABCDEFGHIJKLMNO";
-const COMPARE_VALUE_ORDER: &'static str = "!@#$%^&*";
+const VALUE_ORDER: &str = "12345678";
+const ADDRESS_ORDER: &str = "ABCDEFGHIJKLMNO";
+const COMPARE_VALUE_ORDER: &str = "!@#$%^&*";
 
 const TABLE_6: [Cell; 6] = [
     ['1', '6', '7', '8'],
@@ -57,9 +58,9 @@ const TABLE_8: [Cell; 8] = [
 ];
 
 fn code_pos(symbol: char, table: &[Cell]) -> (usize, usize) {
-    for y in 0..table.len() {
-        for x in 0..table[y].len() {
-            if table[y][x] == symbol {
+    for (y, cell) in table.iter().enumerate() {
+        for (x, value) in cell.iter().enumerate() {
+            if *value == symbol {
                 return (x, y);
             }
         }
@@ -68,17 +69,17 @@ fn code_pos(symbol: char, table: &[Cell]) -> (usize, usize) {
     (9, 4)
 }
 
-fn convert_code(code: &String) -> Vec<u8> {
+fn convert_code(code: &str) -> Result<Vec<u8>, NesError> {
     let mut ret = Vec::new();
 
-    for letter in code.chars() {
+    for (offset, letter) in code.chars().enumerate() {
         match letter_to_bin(letter) {
             Some(value) => ret.push(value),
-            None => panic!("Invalid letter")
+            None => return Err(NesError::InvalidGameGenieLetter { offset, letter })
         };
     }
 
-    ret
+    Ok(ret)
 }
 
 fn get_value(code: &[char], order: &str, table: &[Cell]) -> usize {
@@ -90,7 +91,7 @@ fn get_value(code: &[char], order: &str, table: &[Cell]) -> usize {
         let (x, y) = code_pos(symbol, table);
 
         // Linked code letter
-        let letter = code[y as usize];
+        let letter = code[y];
 
         // Letter value
         match letter_to_bin(letter) {
@@ -119,7 +120,7 @@ fn get_value(code: &[char], order: &str, table: &[Cell]) -> usize {
 /// let code = String::from("SZEKKIVG");
 /// 
 /// let mut d = NesGameGenie::new(code);
-/// d.decode();
+/// d.decode().unwrap();
 /// 
 /// let address = d.address;
 /// let value = d.value;
@@ -157,14 +158,16 @@ impl NesGameGenie {
     }
 
     /// Decoding the Game Genie code
-    pub fn decode(&mut self) {
-        self.code_genie = convert_code(&self.code);
+    pub fn decode(&mut self) -> Result<(), NesError> {
+        self.code_genie = convert_code(&self.code)?;
 
-        match self.code.len() {
+        match self.code_genie.len() {
             6 => self.decode_with_table(&TABLE_6),
             8 => self.decode_with_table(&TABLE_8),
-            _ => panic!("Invalid code lenght")
-        }
+            len => return Err(NesError::InvalidGameGenieLength(len))
+        };
+
+        Ok(())
     }
 }
 
@@ -172,25 +175,27 @@ impl NesUtil for NesGameGenie { }
 
 impl Util for NesGameGenie {
     /// Decoding the Game Genie code
-    fn run(&mut self) {
-        self.decode();
+    fn run(&mut self) -> Result<(), NesError> {
+        self.decode()
     }
 }
 
 impl Save for NesGameGenie {
     /// Dumping in a formatted way
-    fn save_as(&mut self, _path: &str) {
+    fn save_as(&mut self, _path: &str) -> Result<(), NesError> {
         println!("Address 0x{:02x?}", self.address);
         println!("Value 0x{:02x?}", self.value);
 
         if let Some(compare) = self.compare {
             println!("Compare value 0x{:02x?}", compare);
         }
+
+        Ok(())
     }
 
     /// Same as `save_as`
-    fn save(&mut self) {
-        self.save_as("");
+    fn save(&mut self) -> Result<(), NesError> {
+        self.save_as("")
     }
 }
 
@@ -206,15 +211,15 @@ impl Save for NesGameGenie {
 /// // Pac Man - Both players have infinite lives
 /// let code = String::from("SZEKKIVG");
 /// 
-/// let data = decode(code);
+/// let data = decode(code).unwrap();
 /// 
 /// let address = data.address;
 /// let value = data.value;
 /// let compare_value = data.compare;
 /// ```
-pub fn decode(code: String) -> NesGameGenie {
+pub fn decode(code: String) -> Result<NesGameGenie, NesError> {
     let mut d = NesGameGenie::new(code);
-    d.decode();
+    d.decode()?;
 
-    d
+    Ok(d)
 }
@@ -1,3 +1,8 @@
+#![allow(clippy::module_inception)]
+
+/// 6502 assembler
+pub mod assembler;
+
 /// Manages CHR
 pub mod chr;
 
@@ -7,10 +12,17 @@ pub mod disassembler;
 /// Manages Game Genie codes
 pub mod game_genie;
 
+/// Mappers database
+pub mod mapper;
+
 /// Contains traits
 pub mod models;
 
 /// Pseudo Random Number Generator
 pub mod prng;
 
-mod utils;
+/// Nes ROM file
+pub mod rom;
+
+/// Errors, opcodes, addressing modes, registers and helpers
+pub mod utils;
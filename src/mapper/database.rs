@@ -0,0 +1,245 @@
+use crate::mapper::mapper::{
+    Bank::{
+        Fixed,
+        FixedFromEnd,
+        Switchable
+    },
+    BankWindow,
+    Mapper,
+    MapperRegister,
+    register,
+    window
+};
+
+const KB: usize = 0x400;
+
+// PRG windows
+
+const PRG_FIXED_32K: [BankWindow; 2] = [
+    window(0x8000, 16 * KB, Fixed(0)),
+    window(0xc000, 16 * KB, FixedFromEnd(0))
+];
+
+const PRG_SWITCH_32K: [BankWindow; 1] = [
+    window(0x8000, 32 * KB, Switchable)
+];
+
+const PRG_SWITCH_16K_FIXED_LAST: [BankWindow; 2] = [
+    window(0x8000, 16 * KB, Switchable),
+    window(0xc000, 16 * KB, FixedFromEnd(0))
+];
+
+const PRG_SWITCH_8K_8K_FIXED_16K: [BankWindow; 4] = [
+    window(0x8000, 8 * KB, Switchable),
+    window(0xa000, 8 * KB, Switchable),
+    window(0xc000, 8 * KB, FixedFromEnd(1)),
+    window(0xe000, 8 * KB, FixedFromEnd(0))
+];
+
+const PRG_VRC6: [BankWindow; 3] = [
+    window(0x8000, 16 * KB, Switchable),
+    window(0xc000, 8 * KB, Switchable),
+    window(0xe000, 8 * KB, FixedFromEnd(0))
+];
+
+const PRG_SWITCH_8K_X3_FIXED_LAST: [BankWindow; 4] = [
+    window(0x8000, 8 * KB, Switchable),
+    window(0xa000, 8 * KB, Switchable),
+    window(0xc000, 8 * KB, Switchable),
+    window(0xe000, 8 * KB, FixedFromEnd(0))
+];
+
+const PRG_FME7: [BankWindow; 5] = [
+    window(0x6000, 8 * KB, Switchable),
+    PRG_SWITCH_8K_X3_FIXED_LAST[0],
+    PRG_SWITCH_8K_X3_FIXED_LAST[1],
+    PRG_SWITCH_8K_X3_FIXED_LAST[2],
+    PRG_SWITCH_8K_X3_FIXED_LAST[3]
+];
+
+// CHR windows
+
+const CHR_FIXED_8K: [BankWindow; 1] = [
+    window(0x0000, 8 * KB, Fixed(0))
+];
+
+const CHR_SWITCH_8K: [BankWindow; 1] = [
+    window(0x0000, 8 * KB, Switchable)
+];
+
+const CHR_SWITCH_4K: [BankWindow; 2] = [
+    window(0x0000, 4 * KB, Switchable),
+    window(0x1000, 4 * KB, Switchable)
+];
+
+const CHR_MMC3: [BankWindow; 6] = [
+    window(0x0000, 2 * KB, Switchable),
+    window(0x0800, 2 * KB, Switchable),
+    window(0x1000, KB, Switchable),
+    window(0x1400, KB, Switchable),
+    window(0x1800, KB, Switchable),
+    window(0x1c00, KB, Switchable)
+];
+
+const CHR_SWITCH_1K: [BankWindow; 8] = [
+    window(0x0000, KB, Switchable),
+    window(0x0400, KB, Switchable),
+    window(0x0800, KB, Switchable),
+    window(0x0c00, KB, Switchable),
+    window(0x1000, KB, Switchable),
+    window(0x1400, KB, Switchable),
+    window(0x1800, KB, Switchable),
+    window(0x1c00, KB, Switchable)
+];
+
+// Registers
+
+const MMC1_REGISTERS: [MapperRegister; 4] = [
+    register(0x8000, 0x9fff, 0xe000, "MMC1_CONTROL", "Serial load (5 writes, bit 7 resets): mirroring (bits 0-1), PRG mode (bits 2-3), CHR mode (bit 4)"),
+    register(0xa000, 0xbfff, 0xe000, "MMC1_CHR0", "Serial load: CHR bank at $0000 (4 KiB, or 8 KiB ignoring bit 0)"),
+    register(0xc000, 0xdfff, 0xe000, "MMC1_CHR1", "Serial load: CHR bank at $1000 (4 KiB mode only)"),
+    register(0xe000, 0xffff, 0xe000, "MMC1_PRG", "Serial load: PRG bank (bits 0-3), PRG RAM disable (bit 4)")
+];
+
+const UXROM_REGISTERS: [MapperRegister; 1] = [
+    register(0x8000, 0xffff, 0x8000, "UXROM_BANK", "PRG bank at $8000 (16 KiB)")
+];
+
+const CNROM_REGISTERS: [MapperRegister; 1] = [
+    register(0x8000, 0xffff, 0x8000, "CNROM_BANK", "CHR bank at $0000 (8 KiB)")
+];
+
+const AXROM_REGISTERS: [MapperRegister; 1] = [
+    register(0x8000, 0xffff, 0x8000, "AXROM_BANK", "PRG bank at $8000 (bits 0-2, 32 KiB), one-screen nametable (bit 4)")
+];
+
+const GXROM_REGISTERS: [MapperRegister; 1] = [
+    register(0x8000, 0xffff, 0x8000, "GXROM_BANK", "CHR bank (bits 0-1, 8 KiB), PRG bank (bits 4-5, 32 KiB)")
+];
+
+const MMC3_REGISTERS: [MapperRegister; 8] = [
+    register(0x8000, 0x9ffe, 0xe001, "MMC3_BANK_SELECT", "Bank register to update (bits 0-2), PRG mode (bit 6), CHR A12 inversion (bit 7)"),
+    register(0x8001, 0x9fff, 0xe001, "MMC3_BANK_DATA", "New value of the selected bank register (R0-R5 CHR, R6-R7 PRG)"),
+    register(0xa000, 0xbffe, 0xe001, "MMC3_MIRRORING", "Nametable mirroring (bit 0: 0 vertical, 1 horizontal)"),
+    register(0xa001, 0xbfff, 0xe001, "MMC3_PRG_RAM_PROTECT", "PRG RAM write protect (bit 6), enable (bit 7)"),
+    register(0xc000, 0xdffe, 0xe001, "MMC3_IRQ_LATCH", "IRQ counter reload value"),
+    register(0xc001, 0xdfff, 0xe001, "MMC3_IRQ_RELOAD", "Reload the IRQ counter at the next scanline"),
+    register(0xe000, 0xfffe, 0xe001, "MMC3_IRQ_DISABLE", "Disable and acknowledge the IRQ"),
+    register(0xe001, 0xffff, 0xe001, "MMC3_IRQ_ENABLE", "Enable the IRQ")
+];
+
+const MMC5_REGISTERS: [MapperRegister; 19] = [
+    register(0x5100, 0x5100, 0xffff, "MMC5_PRG_MODE", "PRG mode (bits 0-1: 32, 16, 16+8 or 8 KiB banks)"),
+    register(0x5101, 0x5101, 0xffff, "MMC5_CHR_MODE", "CHR mode (bits 0-1: 8, 4, 2 or 1 KiB banks)"),
+    register(0x5102, 0x5102, 0xffff, "MMC5_PRG_RAM_PROTECT1", "PRG RAM write protect (must be %10)"),
+    register(0x5103, 0x5103, 0xffff, "MMC5_PRG_RAM_PROTECT2", "PRG RAM write protect (must be %01)"),
+    register(0x5104, 0x5104, 0xffff, "MMC5_EXRAM_MODE", "Extended RAM mode (bits 0-1)"),
+    register(0x5105, 0x5105, 0xffff, "MMC5_NAMETABLE", "Nametable mapping (2 bits per nametable)"),
+    register(0x5106, 0x5106, 0xffff, "MMC5_FILL_TILE", "Fill mode tile"),
+    register(0x5107, 0x5107, 0xffff, "MMC5_FILL_ATTR", "Fill mode attribute (bits 0-1)"),
+    register(0x5113, 0x5113, 0xffff, "MMC5_PRG_RAM_BANK", "PRG RAM bank at $6000 (8 KiB)"),
+    register(0x5114, 0x5117, 0xffff, "MMC5_PRG_BANK", "PRG banks at $8000, $A000, $C000, $E000 (bit 7 selects ROM)"),
+    register(0x5120, 0x512b, 0xffff, "MMC5_CHR_BANK", "CHR banks (sprites $5120-$5127, background $5128-$512B)"),
+    register(0x5130, 0x5130, 0xffff, "MMC5_CHR_UPPER", "Upper CHR bank bits (bits 0-1)"),
+    register(0x5200, 0x5200, 0xffff, "MMC5_SPLIT_MODE", "Vertical split mode"),
+    register(0x5201, 0x5201, 0xffff, "MMC5_SPLIT_SCROLL", "Vertical split scroll"),
+    register(0x5202, 0x5202, 0xffff, "MMC5_SPLIT_BANK", "Vertical split CHR bank"),
+    register(0x5203, 0x5203, 0xffff, "MMC5_IRQ_SCANLINE", "IRQ scanline compare value"),
+    register(0x5204, 0x5204, 0xffff, "MMC5_IRQ_STATUS", "IRQ enable (write bit 7), status (read)"),
+    register(0x5205, 0x5205, 0xffff, "MMC5_MUL_LO", "Multiplicand (write), product low byte (read)"),
+    register(0x5206, 0x5206, 0xffff, "MMC5_MUL_HI", "Multiplier (write), product high byte (read)")
+];
+
+// VRC2/VRC4 boards wire different CPU address lines to the register
+// select pins, registers are given by their $x000 base address
+const VRC2_4_REGISTERS: [MapperRegister; 8] = [
+    register(0x8000, 0x8fff, 0xf000, "VRC_PRG0", "PRG bank at $8000 (or $C000 in VRC4 swap mode, 8 KiB)"),
+    register(0x9000, 0x9fff, 0xf000, "VRC_CONTROL", "Mirroring, VRC4 PRG swap mode"),
+    register(0xa000, 0xafff, 0xf000, "VRC_PRG1", "PRG bank at $A000 (8 KiB)"),
+    register(0xb000, 0xbfff, 0xf000, "VRC_CHR01", "CHR banks 0 and 1 (low/high nibbles, 1 KiB)"),
+    register(0xc000, 0xcfff, 0xf000, "VRC_CHR23", "CHR banks 2 and 3 (low/high nibbles, 1 KiB)"),
+    register(0xd000, 0xdfff, 0xf000, "VRC_CHR45", "CHR banks 4 and 5 (low/high nibbles, 1 KiB)"),
+    register(0xe000, 0xefff, 0xf000, "VRC_CHR67", "CHR banks 6 and 7 (low/high nibbles, 1 KiB)"),
+    register(0xf000, 0xffff, 0xf000, "VRC4_IRQ", "IRQ latch low/high nibbles, control and acknowledge (VRC4)")
+];
+
+const VRC6_REGISTERS: [MapperRegister; 12] = [
+    register(0x8000, 0x8003, 0xf000, "VRC6_PRG16", "PRG bank at $8000 (16 KiB)"),
+    register(0x9000, 0x9002, 0xf000, "VRC6_PULSE1", "Pulse 1 control, period low, period high"),
+    register(0x9003, 0x9003, 0xf003, "VRC6_FREQ_CONTROL", "Audio frequency scaling and halt"),
+    register(0xa000, 0xa002, 0xf000, "VRC6_PULSE2", "Pulse 2 control, period low, period high"),
+    register(0xb000, 0xb002, 0xf000, "VRC6_SAW", "Sawtooth accumulator rate, period low, period high"),
+    register(0xb003, 0xb003, 0xf003, "VRC6_PPU_BANKING", "PPU banking style, mirroring, PRG RAM enable"),
+    register(0xc000, 0xc003, 0xf000, "VRC6_PRG8", "PRG bank at $C000 (8 KiB)"),
+    register(0xd000, 0xd003, 0xf000, "VRC6_CHR0_3", "CHR banks 0-3 (1 KiB)"),
+    register(0xe000, 0xe003, 0xf000, "VRC6_CHR4_7", "CHR banks 4-7 (1 KiB)"),
+    register(0xf000, 0xf000, 0xf003, "VRC6_IRQ_LATCH", "IRQ latch"),
+    register(0xf001, 0xf001, 0xf003, "VRC6_IRQ_CONTROL", "IRQ control"),
+    register(0xf002, 0xf002, 0xf003, "VRC6_IRQ_ACK", "IRQ acknowledge")
+];
+
+// VRC6b (mapper 26) swaps the A0 and A1 address lines, $x001 and $x002
+// are exchanged
+const VRC6B_REGISTERS: [MapperRegister; 12] = [
+    register(0x8000, 0x8003, 0xf000, "VRC6_PRG16", "PRG bank at $8000 (16 KiB)"),
+    register(0x9000, 0x9002, 0xf000, "VRC6_PULSE1", "Pulse 1 control, period high, period low"),
+    register(0x9003, 0x9003, 0xf003, "VRC6_FREQ_CONTROL", "Audio frequency scaling and halt"),
+    register(0xa000, 0xa002, 0xf000, "VRC6_PULSE2", "Pulse 2 control, period high, period low"),
+    register(0xb000, 0xb002, 0xf000, "VRC6_SAW", "Sawtooth accumulator rate, period high, period low"),
+    register(0xb003, 0xb003, 0xf003, "VRC6_PPU_BANKING", "PPU banking style, mirroring, PRG RAM enable"),
+    register(0xc000, 0xc003, 0xf000, "VRC6_PRG8", "PRG bank at $C000 (8 KiB)"),
+    register(0xd000, 0xd003, 0xf000, "VRC6_CHR0_3", "CHR banks 0-3 (1 KiB)"),
+    register(0xe000, 0xe003, 0xf000, "VRC6_CHR4_7", "CHR banks 4-7 (1 KiB)"),
+    register(0xf000, 0xf000, 0xf003, "VRC6_IRQ_LATCH", "IRQ latch"),
+    register(0xf001, 0xf001, 0xf003, "VRC6_IRQ_ACK", "IRQ acknowledge"),
+    register(0xf002, 0xf002, 0xf003, "VRC6_IRQ_CONTROL", "IRQ control")
+];
+
+// VRC7a selects the second register of a pair with A4 ($x010), VRC7b
+// with A3 ($x008), the mask 0xffe7 only matches these two addresses
+const VRC7_REGISTERS: [MapperRegister; 17] = [
+    register(0x8000, 0x8000, 0xffff, "VRC7_PRG0", "PRG bank at $8000 (8 KiB)"),
+    register(0x8008, 0x8010, 0xffe7, "VRC7_PRG1", "PRG bank at $A000 (8 KiB)"),
+    register(0x9000, 0x9000, 0xffff, "VRC7_PRG2", "PRG bank at $C000 (8 KiB)"),
+    register(0x9010, 0x9010, 0xffff, "VRC7_AUDIO_SELECT", "Audio register select"),
+    register(0x9030, 0x9030, 0xffff, "VRC7_AUDIO_DATA", "Audio register data"),
+    register(0xa000, 0xa000, 0xffff, "VRC7_CHR0", "CHR bank at $0000 (1 KiB)"),
+    register(0xa008, 0xa010, 0xffe7, "VRC7_CHR1", "CHR bank at $0400 (1 KiB)"),
+    register(0xb000, 0xb000, 0xffff, "VRC7_CHR2", "CHR bank at $0800 (1 KiB)"),
+    register(0xb008, 0xb010, 0xffe7, "VRC7_CHR3", "CHR bank at $0C00 (1 KiB)"),
+    register(0xc000, 0xc000, 0xffff, "VRC7_CHR4", "CHR bank at $1000 (1 KiB)"),
+    register(0xc008, 0xc010, 0xffe7, "VRC7_CHR5", "CHR bank at $1400 (1 KiB)"),
+    register(0xd000, 0xd000, 0xffff, "VRC7_CHR6", "CHR bank at $1800 (1 KiB)"),
+    register(0xd008, 0xd010, 0xffe7, "VRC7_CHR7", "CHR bank at $1C00 (1 KiB)"),
+    register(0xe000, 0xe000, 0xffff, "VRC7_CONTROL", "Mirroring (bits 0-1), audio silence (bit 6), PRG RAM enable (bit 7)"),
+    register(0xe008, 0xe010, 0xffe7, "VRC7_IRQ_LATCH", "IRQ latch"),
+    register(0xf000, 0xf000, 0xffff, "VRC7_IRQ_CONTROL", "IRQ control"),
+    register(0xf008, 0xf010, 0xffe7, "VRC7_IRQ_ACK", "IRQ acknowledge")
+];
+
+const FME7_REGISTERS: [MapperRegister; 4] = [
+    register(0x8000, 0x9fff, 0xe000, "FME7_COMMAND", "Command register: $0-$7 CHR banks, $8-$B PRG banks, $C mirroring, $D-$F IRQ"),
+    register(0xa000, 0xbfff, 0xe000, "FME7_PARAMETER", "Parameter of the selected command"),
+    register(0xc000, 0xdfff, 0xe000, "FME7_AUDIO_SELECT", "Sunsoft 5B audio register select"),
+    register(0xe000, 0xffff, 0xe000, "FME7_AUDIO_DATA", "Sunsoft 5B audio register data")
+];
+
+/// Known mappers, in iNES number order
+pub const MAPPERS: [Mapper; 16] = [
+    Mapper::new(0, "NROM", (16 * KB, 8 * KB), &PRG_FIXED_32K, &CHR_FIXED_8K, &[]),
+    Mapper::new(1, "MMC1", (16 * KB, 4 * KB), &PRG_SWITCH_16K_FIXED_LAST, &CHR_SWITCH_4K, &MMC1_REGISTERS),
+    Mapper::new(2, "UxROM", (16 * KB, 8 * KB), &PRG_SWITCH_16K_FIXED_LAST, &CHR_FIXED_8K, &UXROM_REGISTERS),
+    Mapper::new(3, "CNROM", (16 * KB, 8 * KB), &PRG_FIXED_32K, &CHR_SWITCH_8K, &CNROM_REGISTERS),
+    Mapper::new(4, "MMC3", (8 * KB, KB), &PRG_SWITCH_8K_8K_FIXED_16K, &CHR_MMC3, &MMC3_REGISTERS),
+    Mapper::new(5, "MMC5", (8 * KB, KB), &PRG_SWITCH_8K_X3_FIXED_LAST, &CHR_SWITCH_1K, &MMC5_REGISTERS),
+    Mapper::new(7, "AxROM", (32 * KB, 8 * KB), &PRG_SWITCH_32K, &CHR_FIXED_8K, &AXROM_REGISTERS),
+    Mapper::new(21, "VRC4a/VRC4c", (8 * KB, KB), &PRG_SWITCH_8K_8K_FIXED_16K, &CHR_SWITCH_1K, &VRC2_4_REGISTERS),
+    Mapper::new(22, "VRC2a", (8 * KB, KB), &PRG_SWITCH_8K_8K_FIXED_16K, &CHR_SWITCH_1K, &VRC2_4_REGISTERS),
+    Mapper::new(23, "VRC2b/VRC4e", (8 * KB, KB), &PRG_SWITCH_8K_8K_FIXED_16K, &CHR_SWITCH_1K, &VRC2_4_REGISTERS),
+    Mapper::new(24, "VRC6a", (8 * KB, KB), &PRG_VRC6, &CHR_SWITCH_1K, &VRC6_REGISTERS),
+    Mapper::new(25, "VRC2c/VRC4b/VRC4d", (8 * KB, KB), &PRG_SWITCH_8K_8K_FIXED_16K, &CHR_SWITCH_1K, &VRC2_4_REGISTERS),
+    Mapper::new(26, "VRC6b", (8 * KB, KB), &PRG_VRC6, &CHR_SWITCH_1K, &VRC6B_REGISTERS),
+    Mapper::new(66, "GxROM", (32 * KB, 8 * KB), &PRG_SWITCH_32K, &CHR_SWITCH_8K, &GXROM_REGISTERS),
+    Mapper::new(69, "FME-7", (8 * KB, KB), &PRG_FME7, &CHR_SWITCH_1K, &FME7_REGISTERS),
+    Mapper::new(85, "VRC7", (8 * KB, KB), &PRG_SWITCH_8K_X3_FIXED_LAST, &CHR_SWITCH_1K, &VRC7_REGISTERS)
+];
@@ -0,0 +1,263 @@
+use crate::mapper::database::MAPPERS;
+
+/// Bank mapped in a CPU/PPU window
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bank {
+    /// Selected through a mapper register
+    Switchable,
+    /// Fixed to the n-th bank
+    Fixed(usize),
+    /// Fixed to the n-th bank from the end (0 is the last one)
+    FixedFromEnd(usize)
+}
+
+impl Bank {
+    /// Bank index for a ROM of `count` banks, `None` if switchable
+    pub fn resolve(&self, count: usize) -> Option<usize> {
+        if count == 0 {
+            return None;
+        }
+
+        match *self {
+            Self::Switchable => None,
+            Self::Fixed(index) => Some(index % count),
+            Self::FixedFromEnd(index) => Some(count - 1 - index % count)
+        }
+    }
+}
+
+/// A CPU (PRG) or PPU (CHR) address range mapped to a bank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BankWindow {
+    pub address: u16,
+    pub size: usize,
+    pub bank: Bank
+}
+
+impl BankWindow {
+    const fn new(address: u16, size: usize, bank: Bank) -> Self {
+        Self { address, size, bank }
+    }
+
+    pub fn contains(&self, address: u16) -> bool {
+        let address = address as usize;
+        let start = self.address as usize;
+
+        address >= start && address < start + self.size
+    }
+}
+
+/// A mapper register, written in the CPU address range `start..=end`
+/// on the addresses where `address & mask == start & mask`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapperRegister {
+    pub start: u16,
+    pub end: u16,
+    pub mask: u16,
+    pub name: &'static str,
+    pub description: &'static str
+}
+
+impl MapperRegister {
+    const fn new(
+        start: u16,
+        end: u16,
+        mask: u16,
+        name: &'static str,
+        description: &'static str
+    ) -> Self {
+        Self { start, end, mask, name, description }
+    }
+
+    pub fn matches(&self, address: u16) -> bool {
+        address >= self.start
+            && address <= self.end
+            && address & self.mask == self.start & self.mask
+    }
+}
+
+/// Mapper description, windows are given for the power-on configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mapper {
+    pub number: u16,
+    pub name: &'static str,
+    /// PRG ROM bank granularity in bytes
+    pub prg_bank_size: usize,
+    /// CHR ROM bank granularity in bytes
+    pub chr_bank_size: usize,
+    pub prg_windows: &'static [BankWindow],
+    pub chr_windows: &'static [BankWindow],
+    pub registers: &'static [MapperRegister]
+}
+
+impl Mapper {
+    /// First CPU address of the PRG ROM
+    pub const PRG_ROM_ADDRESS: u16 = 0x8000;
+
+    pub(crate) const fn new(
+        number: u16,
+        name: &'static str,
+        banks: (usize, usize),
+        prg_windows: &'static [BankWindow],
+        chr_windows: &'static [BankWindow],
+        registers: &'static [MapperRegister]
+    ) -> Self {
+        let (prg_bank_size, chr_bank_size) = banks;
+
+        Self {
+            number,
+            name,
+            prg_bank_size,
+            chr_bank_size,
+            prg_windows,
+            chr_windows,
+            registers
+        }
+    }
+
+    /// PRG window holding the CPU address
+    pub fn prg_window(&self, address: u16) -> Option<&BankWindow> {
+        self.prg_windows
+            .iter()
+            .find(|window| window.contains(address))
+    }
+
+    /// CHR window holding the PPU address
+    pub fn chr_window(&self, address: u16) -> Option<&BankWindow> {
+        self.chr_windows
+            .iter()
+            .find(|window| window.contains(address))
+    }
+
+    /// Register written at the CPU address
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use nes_utils::mapper::mapper::get_mapper;
+    ///
+    /// let vrc7 = get_mapper(85).unwrap();
+    /// let name = |address| vrc7.register(address).map(|register| register.name);
+    ///
+    /// assert_eq!(name(0xb000), Some("VRC7_CHR2"));
+    /// assert_eq!(name(0xc008), Some("VRC7_CHR5"));
+    /// assert_eq!(name(0xd008), Some("VRC7_CHR7"));
+    /// assert_eq!(name(0xd010), Some("VRC7_CHR7"));
+    /// assert_eq!(name(0x800c), None);
+    ///
+    /// // VRC6a and VRC6b, A0 and A1 swapped
+    /// let vrc6a = get_mapper(24).unwrap();
+    /// let vrc6b = get_mapper(26).unwrap();
+    /// let names = |address| (
+    ///     vrc6a.register(address).map(|register| register.name),
+    ///     vrc6b.register(address).map(|register| register.name)
+    /// );
+    ///
+    /// assert_eq!(names(0xf001), (Some("VRC6_IRQ_CONTROL"), Some("VRC6_IRQ_ACK")));
+    /// assert_eq!(names(0xf002), (Some("VRC6_IRQ_ACK"), Some("VRC6_IRQ_CONTROL")));
+    /// ```
+    pub fn register(&self, address: u16) -> Option<&MapperRegister> {
+        self.registers
+            .iter()
+            .find(|register| register.matches(address))
+    }
+
+    /// Number of PRG banks for a PRG ROM of `size` bytes
+    pub fn prg_bank_count(&self, size: usize) -> usize {
+        size.div_ceil(self.prg_bank_size)
+    }
+
+    /// Number of CHR banks for a CHR ROM of `size` bytes
+    pub fn chr_bank_count(&self, size: usize) -> usize {
+        size.div_ceil(self.chr_bank_size)
+    }
+
+    /// PRG bank fixed at the CPU address for a PRG ROM of `size` bytes
+    pub fn fixed_prg_bank(&self, address: u16, size: usize) -> Option<usize> {
+        let window = self.prg_window(address)?;
+        let count = self.prg_bank_count(size);
+        let bank = window.bank.resolve(count)?;
+        let offset = (address - window.address) as usize / self.prg_bank_size;
+
+        Some((bank + offset) % count)
+    }
+
+    /// CPU address where a PRG bank is mapped.
+    ///
+    /// Fixed banks use their fixed window (the highest one if the bank
+    /// is mirrored), the other ones use the first switchable PRG ROM window.
+    pub fn prg_bank_address(&self, bank: usize, count: usize) -> u16 {
+        let windows = self.prg_windows
+            .iter()
+            .filter(|window| window.address >= Mapper::PRG_ROM_ADDRESS);
+
+        // Windows bigger than the granularity hold consecutive banks
+        let fixed = windows
+            .clone()
+            .filter_map(|window| {
+                let first = window.bank.resolve(count)?;
+                let n = (bank + count - first) % count;
+
+                (n < self.prg_window_banks(window))
+                    .then(|| window.address + (n * self.prg_bank_size) as u16)
+            })
+            .max();
+
+        if let Some(address) = fixed {
+            return address;
+        }
+
+        match windows.clone().find(|window| window.bank == Bank::Switchable) {
+            Some(window) => {
+                let n = bank % self.prg_window_banks(window);
+
+                window.address + (n * self.prg_bank_size) as u16
+            },
+            None => Mapper::PRG_ROM_ADDRESS
+        }
+    }
+
+    fn prg_window_banks(&self, window: &BankWindow) -> usize {
+        (window.size / self.prg_bank_size).max(1)
+    }
+}
+
+pub(crate) const fn window(address: u16, size: usize, bank: Bank) -> BankWindow {
+    BankWindow::new(address, size, bank)
+}
+
+pub(crate) const fn register(
+    start: u16,
+    end: u16,
+    mask: u16,
+    name: &'static str,
+    description: &'static str
+) -> MapperRegister {
+    MapperRegister::new(start, end, mask, name, description)
+}
+
+/// Get a mapper description from its iNES/NES 2.0 number
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::mapper::mapper::get_mapper;
+///
+/// // UxROM, 8 banks of 16 KiB
+/// let mapper = get_mapper(2).unwrap();
+///
+/// assert_eq!(mapper.name, "UxROM");
+/// assert_eq!(mapper.fixed_prg_bank(0xc000, 0x20000), Some(7));
+/// assert_eq!(mapper.fixed_prg_bank(0x8000, 0x20000), None);
+/// assert_eq!(mapper.prg_bank_address(7, 8), 0xc000);
+/// assert_eq!(mapper.prg_bank_address(3, 8), 0x8000);
+/// ```
+pub fn get_mapper(number: u16) -> Option<&'static Mapper> {
+    MAPPERS
+        .iter()
+        .find(|mapper| mapper.number == number)
+}
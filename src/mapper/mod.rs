@@ -0,0 +1,5 @@
+/// Mapper description (banks, windows and registers)
+pub mod mapper;
+
+/// Known mappers
+pub mod database;
@@ -1,5 +1,7 @@
+use crate::utils::error::NesError;
+
 pub trait Header {
-    fn parse(&mut self) -> &mut Self;
+    fn parse(&mut self) -> Result<&mut Self, NesError>;
     fn dump(&self) -> &Self;
-    fn check_magic(&self);
+    fn check_magic(&self) -> Result<(), NesError>;
 }
@@ -1,10 +1,12 @@
+use crate::utils::error::NesError;
+
 pub trait Util {
-    fn run(&mut self);
+    fn run(&mut self) -> Result<(), NesError>;
 }
 
 pub trait Save {
-    fn save(&mut self);
-    fn save_as(&mut self, path: &str);
+    fn save(&mut self) -> Result<(), NesError>;
+    fn save_as(&mut self, path: &str) -> Result<(), NesError>;
 }
 
 pub trait NesUtil: Util + Save { }
@@ -1,7 +1,10 @@
-use crate::models::nesutil_model::{
-    Save,
-    Util,
-    NesUtil
+use crate::{
+    models::nesutil_model::{
+        Save,
+        Util,
+        NesUtil
+    },
+    utils::error::NesError
 };
 
 /// Manage Nes PRNG
@@ -49,9 +52,9 @@ impl NesPrng {
         #[allow(dead_code)]
         fn seed_u16(&self) -> u16 {
             let low = self.seed[1];
-            let high = self.seed[0] << 8;
+            let high = (self.seed[0] as u16) << 8;
             
-            high as u16 | low as u16
+            high | low as u16
         }
         
         fn lsr(&mut self) {
@@ -135,20 +138,24 @@ impl NesUtil for NesPrng { }
 
 impl Util for NesPrng {
     /// Generate a pseudo random number
-    fn run(&mut self) {
+    fn run(&mut self) -> Result<(), NesError> {
         self.random();
+
+        Ok(())
     }
 }
 
 impl Save for NesPrng {
     /// Print the random number
-    fn save_as(&mut self, _path: &str) {
+    fn save_as(&mut self, _path: &str) -> Result<(), NesError> {
         println!("{}", self.a);
+
+        Ok(())
     }
 
     /// Same as `save_as`
-    fn save(&mut self) {
-        println!("{}", self.a);
+    fn save(&mut self) -> Result<(), NesError> {
+        self.save_as("")
     }
 }
 
@@ -0,0 +1,2 @@
+/// Nes ROM file
+pub mod rom;
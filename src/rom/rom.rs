@@ -0,0 +1,164 @@
+use std::fs;
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::slice::Chunks;
+
+use crate::{
+    disassembler::{
+        header::NesHeader,
+        validation::{
+            validate,
+            RomDiagnostic
+        }
+    },
+    mapper::mapper::{
+        get_mapper,
+        Mapper
+    },
+    models::header_model::Header,
+    utils::error::NesError
+};
+
+/// A parsed NES ROM file.
+///
+/// The header is parsed once and every area of the file is exposed as
+/// a slice borrowed from the ROM. Areas missing from a truncated file
+/// are clamped to the file length, `validate` reports them.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```no_run
+/// use std::num::NonZeroUsize;
+///
+/// use nes_utils::rom::rom::NesRom;
+///
+/// let rom = NesRom::from_file("games/game.nes").unwrap();
+///
+/// let mapper = rom.header().mapper();
+/// let banks = rom.prg_banks(NonZeroUsize::new(0x4000).unwrap()).count();
+/// let reset = &rom.prg_rom()[rom.prg_rom().len() - 4..];
+/// ```
+pub struct NesRom {
+    path: String,
+    header: NesHeader,
+    mem: Vec<u8>,
+    trainer: Range<usize>,
+    prg_rom: Range<usize>,
+    chr_rom: Range<usize>,
+    misc_rom: Range<usize>,
+    trailing: Range<usize>
+}
+
+impl NesRom {
+    pub fn new(path: &str, mem: &[u8]) -> Result<Self, NesError> {
+        let mut header = NesHeader::new(mem)?;
+        header.parse()?;
+
+        let len = mem.len();
+        let area = |pos: usize, size: usize| pos.min(len)..pos.saturating_add(size).min(len);
+
+        let trainer = area(NesHeader::HEADER_SIZE, header.trainer_size());
+        let prg_rom = area(NesHeader::HEADER_SIZE + header.trainer_size(), header.prg_rom_size());
+        let chr_rom = area(prg_rom.start.saturating_add(header.prg_rom_size()), header.chr_rom_size());
+        let rest = area(chr_rom.start.saturating_add(header.chr_rom_size()), len);
+
+        // Misc ROMs (NES 2.0) are stored after the CHR ROM
+        let (misc_rom, trailing) = if header.info().misc_rom_count > 0 {
+            (rest, len..len)
+        } else {
+            (rest.start..rest.start, rest)
+        };
+
+        Ok(Self {
+            path: String::from(path),
+            header,
+            mem: mem.to_vec(),
+            trainer,
+            prg_rom,
+            chr_rom,
+            misc_rom,
+            trailing
+        })
+    }
+
+    /// Read and parse a ROM file
+    pub fn from_file(path: &str) -> Result<Self, NesError> {
+        let mem = fs::read(path)
+            .map_err(|source| NesError::FileInvalid {
+                path: String::from(path),
+                source
+            })?;
+
+        Self::new(path, &mem)
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn header(&self) -> &NesHeader {
+        &self.header
+    }
+
+    /// Mapper description, `None` if the mapper is unknown
+    pub fn mapper(&self) -> Option<&'static Mapper> {
+        get_mapper(self.header.mapper())
+    }
+
+    /// The whole file
+    pub fn bytes(&self) -> &[u8] {
+        &self.mem
+    }
+
+    pub fn trainer(&self) -> Option<&[u8]> {
+        if self.trainer.is_empty() {
+            return None;
+        }
+
+        Some(&self.mem[self.trainer.clone()])
+    }
+
+    pub fn prg_rom(&self) -> &[u8] {
+        &self.mem[self.prg_rom.clone()]
+    }
+
+    /// Offset of the PRG ROM in the file
+    pub fn prg_rom_offset(&self) -> usize {
+        self.prg_rom.start
+    }
+
+    pub fn chr_rom(&self) -> &[u8] {
+        &self.mem[self.chr_rom.clone()]
+    }
+
+    /// Offset of the CHR ROM in the file
+    pub fn chr_rom_offset(&self) -> usize {
+        self.chr_rom.start
+    }
+
+    /// PRG ROM split in banks of `size` bytes
+    pub fn prg_banks(&self, size: NonZeroUsize) -> Chunks<'_, u8> {
+        self.prg_rom().chunks(size.get())
+    }
+
+    /// CHR ROM split in banks of `size` bytes
+    pub fn chr_banks(&self, size: NonZeroUsize) -> Chunks<'_, u8> {
+        self.chr_rom().chunks(size.get())
+    }
+
+    pub fn misc_rom(&self) -> &[u8] {
+        &self.mem[self.misc_rom.clone()]
+    }
+
+    /// Bytes after the CHR ROM not declared by the header
+    pub fn trailing_data(&self) -> &[u8] {
+        &self.mem[self.trailing.clone()]
+    }
+
+    /// Check the file against its header
+    pub fn validate(&self) -> Vec<RomDiagnostic> {
+        validate(&self.mem)
+    }
+}
@@ -10,7 +10,9 @@ pub enum AddressingMode {
     ZeroPage,
     ZeroPageX,
     ZeroPageY,
-    Implied
+    Relative,
+    Implied,
+    Accumulator
 }
 
 impl AddressingMode {
@@ -21,12 +23,24 @@ impl AddressingMode {
             Self::AbsoluteY => format!("${}, y", arg),
             Self::Immediate => format!("#${}", arg),
             Self::IndexedIndirect => format!("(${}, x)", arg),
-            Self::Indirect => format!("${}", arg),
+            Self::Indirect => format!("(${})", arg),
             Self::IndirectIndexed => format!("(${}), y", arg),
             Self::ZeroPage => format!("${}", arg),
             Self::ZeroPageX => format!("${}, x", arg),
             Self::ZeroPageY => format!("${}, y", arg),
-            Self::Implied => "".to_string()
+            Self::Relative => format!("${}", arg),
+            Self::Implied => "".to_string(),
+            Self::Accumulator => "a".to_string()
+        }
+    }
+
+    /// Zero page mode with the same indexing as an absolute mode
+    pub fn zero_page(&self) -> Option<Self> {
+        match self {
+            Self::Absolute => Some(Self::ZeroPage),
+            Self::AbsoluteX => Some(Self::ZeroPageX),
+            Self::AbsoluteY => Some(Self::ZeroPageY),
+            _ => None
         }
     }
 
@@ -38,4 +52,23 @@ impl AddressingMode {
 
         ret
     }
-}
\ No newline at end of file
+}
+
+/// Target of a branch at `address` with its signed `offset` operand
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::utils::addressing::relative_target;
+///
+/// // bne *
+/// assert_eq!(relative_target(0xc007, 0xfe), 0xc007);
+/// assert_eq!(relative_target(0xc000, 0x10), 0xc012);
+/// ```
+pub fn relative_target(address: u16, offset: u8) -> u16 {
+    address
+        .wrapping_add(2)
+        .wrapping_add(offset as i8 as u16)
+}
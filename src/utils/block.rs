@@ -1,4 +1,4 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct Block {
     pub pos: usize,
     pub size: usize,
@@ -14,15 +14,7 @@ impl Block {
         }
     }
 
-    pub fn default() -> Self {
-        Block {
-            pos: 0,
-            size: 0,
-            value: None
-        }
-    }
-
-    pub fn value_from(&mut self, mem: &Vec<u8>) {
+    pub fn value_from(&mut self, mem: &[u8]) {
         if mem.len() < self.pos + self.size {
             return;
         }
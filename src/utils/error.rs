@@ -1,22 +1,75 @@
 use std::fmt;
+use std::io;
+use std::error::Error;
+
+use crate::utils::util::vec_bytes_to_string;
 
 #[derive(Debug)]
 pub enum NesError {
-    FileInvalid,
-    WrongNesFormat,
+    FileInvalid {
+        path: String,
+        source: io::Error
+    },
+    Image(image::ImageError),
+    WrongNesFormat {
+        magic: Vec<u8>
+    },
     HeaderNotParsed,
-    NotImplementedOpcode,
-    MissingChr
+    MissingChr,
+    UnexpectedSize {
+        offset: usize,
+        expected: usize,
+        actual: usize
+    },
+    InvalidGameGenieLetter {
+        offset: usize,
+        letter: char
+    },
+    InvalidGameGenieLength(usize),
+    Assembly {
+        path: String,
+        line: usize,
+        reason: String
+    }
 }
 
 impl fmt::Display for NesError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            NesError::FileInvalid => write!(f, "Invalid file"),
-            NesError::WrongNesFormat => write!(f, "Invalid NES format"),
+        match self {
+            NesError::FileInvalid { path, source } => write!(f, "Invalid file {} ({})", path, source),
+            NesError::Image(source) => write!(f, "Image error ({})", source),
+            NesError::WrongNesFormat { magic } => write!(
+                f, "Invalid NES format (magic {})",
+                vec_bytes_to_string(magic)
+            ),
             NesError::HeaderNotParsed => write!(f, "The NES header has not been parsed"),
-            NesError::NotImplementedOpcode => write!(f, "NNot implemented operation code"),
-            NesError::MissingChr => write!(f, "his program doesn't have a CHR ROM"),
+            NesError::MissingChr => write!(f, "This program doesn't have a CHR ROM"),
+            NesError::UnexpectedSize { offset, expected, actual } => write!(
+                f, "Expected 0x{:x} bytes at 0x{:x}, got 0x{:x}",
+                expected, offset, actual
+            ),
+            NesError::InvalidGameGenieLetter { offset, letter } => write!(
+                f, "Invalid Game Genie letter '{}' at {}",
+                letter, offset
+            ),
+            NesError::InvalidGameGenieLength(len) => write!(f, "Invalid Game Genie code length {}", len),
+            NesError::Assembly { path, line, reason } => write!(f, "{}:{}: {}", path, line, reason),
         }
     }
 }
+
+impl Error for NesError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            NesError::FileInvalid { source, .. } => Some(source),
+            NesError::Image(source) => Some(source),
+            _ => None
+        }
+    }
+}
+
+impl From<image::ImageError> for NesError {
+    fn from(value: image::ImageError) -> Self {
+        NesError::Image(value)
+    }
+}
@@ -5,6 +5,8 @@ pub struct OpCode {
     pub code: u8,
     pub mnemonic: &'static str,
     pub len: u8,
+    /// Base cycle count, without page crossing and branch penalties
+    pub cycles: u8,
     pub mode: AddressingMode
 }
 
@@ -13,9 +15,10 @@ impl OpCode {
         code: u8,
         mnemonic: &'static str,
         len: u8,
+        cycles: u8,
         mode: AddressingMode
 ) -> Self {
-        Self { code, mnemonic, len, mode }
+        Self { code, mnemonic, len, cycles, mode }
     }
 
     pub fn arg_to_string(arg_bytes: &[u8]) -> String {
@@ -31,347 +34,644 @@ impl OpCode {
 }
 
 
-pub fn get_nes_opcode(code: &u8) -> Option<OpCode> {
+/// Official 6502 opcode, `None` for the 105 other bytes
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::utils::addressing::AddressingMode;
+/// use nes_utils::utils::opcode::get_official_opcode;
+///
+/// let opcode = get_official_opcode(&0xea).unwrap();
+///
+/// assert_eq!(opcode.mnemonic, "nop");
+/// assert_eq!((opcode.len, opcode.cycles), (1, 2));
+/// assert_eq!(opcode.mode, AddressingMode::Implied);
+/// assert!(get_official_opcode(&0x02).is_none());
+/// ```
+pub fn get_official_opcode(code: &u8) -> Option<OpCode> {
     let ret = match code {
-        0x69 => ("adc", 2, AddressingMode::Immediate),
-        0x65 => ("adc", 2, AddressingMode::ZeroPage),
-        0x75 => ("adc", 2, AddressingMode::ZeroPageX),
-        0x6d => ("adc", 3, AddressingMode::Absolute),
-        0x7d => ("adc", 3, AddressingMode::AbsoluteX),
-        0x79 => ("adc", 3, AddressingMode::AbsoluteY),
-        0x61 => ("adc", 2, AddressingMode::IndexedIndirect),
-        0x71 => ("adc", 2, AddressingMode::IndirectIndexed),
+        0x69 => ("adc", 2, 2, AddressingMode::Immediate),
+        0x65 => ("adc", 2, 3, AddressingMode::ZeroPage),
+        0x75 => ("adc", 2, 4, AddressingMode::ZeroPageX),
+        0x6d => ("adc", 3, 4, AddressingMode::Absolute),
+        0x7d => ("adc", 3, 4, AddressingMode::AbsoluteX),
+        0x79 => ("adc", 3, 4, AddressingMode::AbsoluteY),
+        0x61 => ("adc", 2, 6, AddressingMode::IndexedIndirect),
+        0x71 => ("adc", 2, 5, AddressingMode::IndirectIndexed),
+
+        0x29 => ("and", 2, 2, AddressingMode::Immediate),
+        0x25 => ("and", 2, 3, AddressingMode::ZeroPage),
+        0x35 => ("and", 2, 4, AddressingMode::ZeroPageX),
+        0x2d => ("and", 3, 4, AddressingMode::Absolute),
+        0x3d => ("and", 3, 4, AddressingMode::AbsoluteX),
+        0x39 => ("and", 3, 4, AddressingMode::AbsoluteY),
+        0x21 => ("and", 2, 6, AddressingMode::IndexedIndirect),
+        0x31 => ("and", 2, 5, AddressingMode::IndirectIndexed),
+
+        0x0a => ("asl", 1, 2, AddressingMode::Accumulator),
+        0x06 => ("asl", 2, 5, AddressingMode::ZeroPage),
+        0x16 => ("asl", 2, 6, AddressingMode::ZeroPageX),
+        0x0e => ("asl", 3, 6, AddressingMode::Absolute),
+        0x1e => ("asl", 3, 7, AddressingMode::AbsoluteX),
+
+        0x90 => ("bcc", 2, 2, AddressingMode::Relative),
+
+        0xb0 => ("bcs", 2, 2, AddressingMode::Relative),
+
+        0xf0 => ("beq", 2, 2, AddressingMode::Relative),
+
+        0x24 => ("bit", 2, 3, AddressingMode::ZeroPage),
+        0x2c => ("bit", 3, 4, AddressingMode::Absolute),
+
+        0x30 => ("bmi", 2, 2, AddressingMode::Relative),
+
+        0xd0 => ("bne", 2, 2, AddressingMode::Relative),
+
+        0x10 => ("bpl", 2, 2, AddressingMode::Relative),
+
+        0x00 => ("brk", 1, 7, AddressingMode::Implied),
+
+        0x50 => ("bvc", 2, 2, AddressingMode::Relative),
+
+        0x70 => ("bvs", 2, 2, AddressingMode::Relative),
+
+        0x18 => ("clc", 1, 2, AddressingMode::Implied),
+
+        0xd8 => ("cld", 1, 2, AddressingMode::Implied),
+
+        0x58 => ("cli", 1, 2, AddressingMode::Implied),
+
+        0xb8 => ("clv", 1, 2, AddressingMode::Implied),
+
+        0xc9 => ("cmp", 2, 2, AddressingMode::Immediate),
+        0xc5 => ("cmp", 2, 3, AddressingMode::ZeroPage),
+        0xd5 => ("cmp", 2, 4, AddressingMode::ZeroPageX),
+        0xcd => ("cmp", 3, 4, AddressingMode::Absolute),
+        0xdd => ("cmp", 3, 4, AddressingMode::AbsoluteX),
+        0xd9 => ("cmp", 3, 4, AddressingMode::AbsoluteY),
+        0xc1 => ("cmp", 2, 6, AddressingMode::IndexedIndirect),
+        0xd1 => ("cmp", 2, 5, AddressingMode::IndirectIndexed),
+
+        0xe0 => ("cpx", 2, 2, AddressingMode::Immediate),
+        0xe4 => ("cpx", 2, 3, AddressingMode::ZeroPage),
+        0xec => ("cpx", 3, 4, AddressingMode::Absolute),
+
+        0xc0 => ("cpy", 2, 2, AddressingMode::Immediate),
+        0xc4 => ("cpy", 2, 3, AddressingMode::ZeroPage),
+        0xcc => ("cpy", 3, 4, AddressingMode::Absolute),
+
+        0xc6 => ("dec", 2, 5, AddressingMode::ZeroPage),
+        0xd6 => ("dec", 2, 6, AddressingMode::ZeroPageX),
+        0xce => ("dec", 3, 6, AddressingMode::Absolute),
+        0xde => ("dec", 3, 7, AddressingMode::AbsoluteX),
+
+        0xca => ("dex", 1, 2, AddressingMode::Implied),
+
+        0x88 => ("dey", 1, 2, AddressingMode::Implied),
+
+        0x49 => ("eor", 2, 2, AddressingMode::Immediate),
+        0x45 => ("eor", 2, 3, AddressingMode::ZeroPage),
+        0x55 => ("eor", 2, 4, AddressingMode::ZeroPageX),
+        0x4d => ("eor", 3, 4, AddressingMode::Absolute),
+        0x5d => ("eor", 3, 4, AddressingMode::AbsoluteX),
+        0x59 => ("eor", 3, 4, AddressingMode::AbsoluteY),
+        0x41 => ("eor", 2, 6, AddressingMode::IndexedIndirect),
+        0x51 => ("eor", 2, 5, AddressingMode::IndirectIndexed),
+
+        0xe6 => ("inc", 2, 5, AddressingMode::ZeroPage),
+        0xf6 => ("inc", 2, 6, AddressingMode::ZeroPageX),
+        0xee => ("inc", 3, 6, AddressingMode::Absolute),
+        0xfe => ("inc", 3, 7, AddressingMode::AbsoluteX),
+
+        0xe8 => ("inx", 1, 2, AddressingMode::Implied),
+
+        0xc8 => ("iny", 1, 2, AddressingMode::Implied),
+
+        0x4c => ("jmp", 3, 3, AddressingMode::Absolute),
+        0x6c => ("jmp", 3, 5, AddressingMode::Indirect),
+
+        0x20 => ("jsr", 3, 6, AddressingMode::Absolute),
+
+        0xa9 => ("lda", 2, 2, AddressingMode::Immediate),
+        0xa5 => ("lda", 2, 3, AddressingMode::ZeroPage),
+        0xb5 => ("lda", 2, 4, AddressingMode::ZeroPageX),
+        0xad => ("lda", 3, 4, AddressingMode::Absolute),
+        0xbd => ("lda", 3, 4, AddressingMode::AbsoluteX),
+        0xb9 => ("lda", 3, 4, AddressingMode::AbsoluteY),
+        0xa1 => ("lda", 2, 6, AddressingMode::IndexedIndirect),
+        0xb1 => ("lda", 2, 5, AddressingMode::IndirectIndexed),
+
+        0xa2 => ("ldx", 2, 2, AddressingMode::Immediate),
+        0xa6 => ("ldx", 2, 3, AddressingMode::ZeroPage),
+        0xb6 => ("ldx", 2, 4, AddressingMode::ZeroPageY),
+        0xae => ("ldx", 3, 4, AddressingMode::Absolute),
+        0xbe => ("ldx", 3, 4, AddressingMode::AbsoluteY),
 
-        0x29 => ("and", 2, AddressingMode::Immediate),
-        0x25 => ("and", 2, AddressingMode::ZeroPage),
-        0x35 => ("and", 2, AddressingMode::ZeroPageX),
-        0x2d => ("and", 3, AddressingMode::Absolute),
-        0x3d => ("and", 3, AddressingMode::AbsoluteX),
-        0x39 => ("and", 3, AddressingMode::AbsoluteY),
-        0x21 => ("and", 2, AddressingMode::IndexedIndirect),
-        0x31 => ("and", 2, AddressingMode::IndirectIndexed),
+        0xa0 => ("ldy", 2, 2, AddressingMode::Immediate),
+        0xa4 => ("ldy", 2, 3, AddressingMode::ZeroPage),
+        0xb4 => ("ldy", 2, 4, AddressingMode::ZeroPageX),
+        0xac => ("ldy", 3, 4, AddressingMode::Absolute),
+        0xbc => ("ldy", 3, 4, AddressingMode::AbsoluteX),
 
-        0x0a => ("asl", 1, AddressingMode::Implied),
-        0x06 => ("asl", 2, AddressingMode::ZeroPage),
-        0x16 => ("asl", 2, AddressingMode::ZeroPageX),
-        0x0e => ("asl", 3, AddressingMode::Absolute),
-        0x1e => ("asl", 3, AddressingMode::AbsoluteX),
+        0x4a => ("lsr", 1, 2, AddressingMode::Accumulator),
+        0x46 => ("lsr", 2, 5, AddressingMode::ZeroPage),
+        0x56 => ("lsr", 2, 6, AddressingMode::ZeroPageX),
+        0x4e => ("lsr", 3, 6, AddressingMode::Absolute),
+        0x5e => ("lsr", 3, 7, AddressingMode::AbsoluteX),
 
-        0x90 => ("bcc", 2, AddressingMode::Implied),
+        0xea => ("nop", 1, 2, AddressingMode::Implied),
 
-        0xb0 => ("bcs", 2, AddressingMode::Implied),
-
-        0xf0 => ("beq", 2, AddressingMode::Implied),
-
-        0x24 => ("bit", 2, AddressingMode::ZeroPage),
-        0x2c => ("bit", 3, AddressingMode::Absolute),
-
-        0x30 => ("bmi", 2, AddressingMode::Absolute),
-
-        0xd0 => ("bne", 2, AddressingMode::Absolute),
-
-        0x10 => ("bpl", 2, AddressingMode::Absolute),
-
-        0x00 => ("brk", 1, AddressingMode::Implied),
-
-        0x50 => ("bvc", 2, AddressingMode::Absolute),
-
-        0x70 => ("bvs", 2, AddressingMode::Absolute),
-
-        0x18 => ("clc", 1, AddressingMode::Implied),
+        0x09 => ("ora", 2, 2, AddressingMode::Immediate),
+        0x05 => ("ora", 2, 3, AddressingMode::ZeroPage),
+        0x15 => ("ora", 2, 4, AddressingMode::ZeroPageX),
+        0x0d => ("ora", 3, 4, AddressingMode::Absolute),
+        0x1d => ("ora", 3, 4, AddressingMode::AbsoluteX),
+        0x19 => ("ora", 3, 4, AddressingMode::AbsoluteY),
+        0x01 => ("ora", 2, 6, AddressingMode::IndexedIndirect),
+        0x11 => ("ora", 2, 5, AddressingMode::IndirectIndexed),
 
-        0xd8 => ("cld", 1, AddressingMode::Implied),
+        0x48 => ("pha", 1, 3, AddressingMode::Implied),
 
-        0x58 => ("cli", 1, AddressingMode::Implied),
+        0x08 => ("php", 1, 3, AddressingMode::Implied),
 
-        0xb8 => ("clv", 1, AddressingMode::Implied),
-
-        0xc9 => ("cmp", 2, AddressingMode::Immediate),
-        0xc5 => ("cmp", 2, AddressingMode::ZeroPage),
-        0xd5 => ("cmp", 2, AddressingMode::ZeroPageX),
-        0xcd => ("cmp", 3, AddressingMode::Absolute),
-        0xdd => ("cmp", 3, AddressingMode::AbsoluteX),
-        0xd9 => ("cmp", 3, AddressingMode::AbsoluteY),
-        0xc1 => ("cmp", 2, AddressingMode::IndexedIndirect),
-        0xd1 => ("cmp", 2, AddressingMode::IndirectIndexed),
+        0x68 => ("pla", 1, 4, AddressingMode::Implied),
 
-        0xe0 => ("cpx", 2, AddressingMode::Immediate),
-        0xe4 => ("cpx", 2, AddressingMode::ZeroPage),
-        0xec => ("cpx", 3, AddressingMode::Absolute),
+        0x28 => ("plp", 1, 4, AddressingMode::Implied),
 
-        0xc0 => ("cpy", 2, AddressingMode::Immediate),
-        0xc4 => ("cpy", 2, AddressingMode::ZeroPage),
-        0xcc => ("cpy", 3, AddressingMode::Absolute),
-
-        0xc6 => ("dec", 2, AddressingMode::ZeroPage),
-        0xd6 => ("dec", 2, AddressingMode::ZeroPageX),
-        0xce => ("dec", 3, AddressingMode::Absolute),
-        0xde => ("dec", 3, AddressingMode::AbsoluteX),
+        0x2a => ("rol", 1, 2, AddressingMode::Accumulator),
+        0x26 => ("rol", 2, 5, AddressingMode::ZeroPage),
+        0x36 => ("rol", 2, 6, AddressingMode::ZeroPageX),
+        0x2e => ("rol", 3, 6, AddressingMode::Absolute),
+        0x3e => ("rol", 3, 7, AddressingMode::AbsoluteX),
 
-        0xca => ("dex", 1, AddressingMode::Implied),
+        0x6a => ("ror", 1, 2, AddressingMode::Accumulator),
+        0x66 => ("ror", 2, 5, AddressingMode::ZeroPage),
+        0x76 => ("ror", 2, 6, AddressingMode::ZeroPageX),
+        0x6e => ("ror", 3, 6, AddressingMode::Absolute),
+        0x7e => ("ror", 3, 7, AddressingMode::AbsoluteX),
 
-        0x88 => ("dey", 1, AddressingMode::Implied),
+        0x40 => ("rti", 1, 6, AddressingMode::Implied),
 
-        0x49 => ("eor", 2, AddressingMode::Immediate),
-        0x45 => ("eor", 2, AddressingMode::ZeroPage),
-        0x55 => ("eor", 2, AddressingMode::ZeroPageX),
-        0x4d => ("eor", 3, AddressingMode::Absolute),
-        0x5d => ("eor", 3, AddressingMode::AbsoluteX),
-        0x59 => ("eor", 3, AddressingMode::AbsoluteY),
-        0x41 => ("eor", 2, AddressingMode::IndexedIndirect),
-        0x51 => ("eor", 2, AddressingMode::IndirectIndexed),
-
-        0xe6 => ("inc", 2, AddressingMode::ZeroPage),
-        0xf6 => ("inc", 2, AddressingMode::ZeroPageX),
-        0xee => ("inc", 3, AddressingMode::Absolute),
-        0xfe => ("inc", 3, AddressingMode::AbsoluteX),
-
-        0xe8 => ("inx", 1, AddressingMode::Implied),
+        0x60 => ("rts", 1, 6, AddressingMode::Implied),
 
-        0xc8 => ("iny", 1, AddressingMode::Implied),
+        0xe9 => ("sbc", 2, 2, AddressingMode::Immediate),
+        0xe5 => ("sbc", 2, 3, AddressingMode::ZeroPage),
+        0xf5 => ("sbc", 2, 4, AddressingMode::ZeroPageX),
+        0xed => ("sbc", 3, 4, AddressingMode::Absolute),
+        0xfd => ("sbc", 3, 4, AddressingMode::AbsoluteX),
+        0xf9 => ("sbc", 3, 4, AddressingMode::AbsoluteY),
+        0xe1 => ("sbc", 2, 6, AddressingMode::IndexedIndirect),
+        0xf1 => ("sbc", 2, 5, AddressingMode::IndirectIndexed),
 
-        0x4c => ("jmp", 3, AddressingMode::Absolute), 
-        0x6c => ("jmp", 3, AddressingMode::Indirect),
+        0x38 => ("sec", 1, 2, AddressingMode::Implied),
 
-        0x20 => ("jsr", 3, AddressingMode::Absolute),
+        0xf8 => ("sed", 1, 2, AddressingMode::Implied),
 
-        0xa9 => ("lda", 2, AddressingMode::Immediate),
-        0xa5 => ("lda", 2, AddressingMode::ZeroPage),
-        0xb5 => ("lda", 2, AddressingMode::ZeroPageX),
-        0xad => ("lda", 3, AddressingMode::Absolute),
-        0xbd => ("lda", 3, AddressingMode::AbsoluteX),
-        0xb9 => ("lda", 3, AddressingMode::AbsoluteY),
-        0xa1 => ("lda", 2, AddressingMode::IndexedIndirect),
-        0xb1 => ("lda", 2, AddressingMode::IndirectIndexed),
-    
-        0xa2 => ("ldx", 2, AddressingMode::Immediate),
-        0xa6 => ("ldx", 2, AddressingMode::ZeroPage),
-        0xb6 => ("ldx", 2, AddressingMode::ZeroPageY),
-        0xae => ("ldx", 3, AddressingMode::Absolute),
-        0xbe => ("ldx", 3, AddressingMode::AbsoluteY),
+        0x78 => ("sei", 1, 2, AddressingMode::Implied),
 
-        0xa0 => ("ldy", 2, AddressingMode::Immediate),
-        0xa4 => ("ldy", 2, AddressingMode::ZeroPage),
-        0xb4 => ("ldy", 2, AddressingMode::ZeroPageX),
-        0xac => ("ldy", 3, AddressingMode::Absolute),
-        0xbc => ("ldy", 3, AddressingMode::AbsoluteX),
+        0x85 => ("sta", 2, 3, AddressingMode::ZeroPage),
+        0x95 => ("sta", 2, 4, AddressingMode::ZeroPageX),
+        0x8d => ("sta", 3, 4, AddressingMode::Absolute),
+        0x9d => ("sta", 3, 5, AddressingMode::AbsoluteX),
+        0x99 => ("sta", 3, 5, AddressingMode::AbsoluteY),
+        0x81 => ("sta", 2, 6, AddressingMode::IndexedIndirect),
+        0x91 => ("sta", 2, 6, AddressingMode::IndirectIndexed),
 
-        0x4a => ("lsr", 1, AddressingMode::Implied),
-        0x46 => ("lsr", 2, AddressingMode::ZeroPage),
-        0x56 => ("lsr", 2, AddressingMode::ZeroPageX),
-        0x4e => ("lsr", 3, AddressingMode::Absolute),
-        0x5e => ("lsr", 3, AddressingMode::AbsoluteX),
-        
-        0xea => ("hex", 3, AddressingMode::AbsoluteX),
+        0x86 => ("stx", 2, 3, AddressingMode::ZeroPage),
+        0x96 => ("stx", 2, 4, AddressingMode::ZeroPageY),
+        0x8e => ("stx", 3, 4, AddressingMode::Absolute),
 
-        0x09 => ("ora", 2, AddressingMode::Immediate),
-        0x05 => ("ora", 2, AddressingMode::ZeroPage),
-        0x15 => ("ora", 2, AddressingMode::ZeroPageX),
-        0x0d => ("ora", 3, AddressingMode::Absolute),
-        0x1d => ("ora", 3, AddressingMode::AbsoluteX),
-        0x19 => ("ora", 3, AddressingMode::AbsoluteY),
-        0x01 => ("ora", 2, AddressingMode::IndexedIndirect),
-        0x11 => ("ora", 2, AddressingMode::IndirectIndexed),
+        0x84 => ("sty", 2, 3, AddressingMode::ZeroPage),
+        0x94 => ("sty", 2, 4, AddressingMode::ZeroPageX),
+        0x8c => ("sty", 3, 4, AddressingMode::Absolute),
 
-        0x48 => ("pha", 1, AddressingMode::Implied),
+        0xaa => ("tax", 1, 2, AddressingMode::Implied),
 
-        0x08 => ("php", 1, AddressingMode::Implied),
+        0xa8 => ("tay", 1, 2, AddressingMode::Implied),
 
-        0x68 => ("pla", 1, AddressingMode::Implied),
+        0xba => ("tsx", 1, 2, AddressingMode::Implied),
 
-        0x28 => ("plp", 1, AddressingMode::Implied),
+        0x8a => ("txa", 1, 2, AddressingMode::Implied),
 
-        0x2a => ("rol", 1, AddressingMode::Implied),
-        0x26 => ("rol", 2, AddressingMode::ZeroPage),
-        0x36 => ("rol", 2, AddressingMode::ZeroPageX),
-        0x2e => ("rol", 3, AddressingMode::Absolute),
-        0x3e => ("rol", 3, AddressingMode::AbsoluteX),
-
-        0x6a => ("ror", 1, AddressingMode::Implied),
-        0x66 => ("ror", 2, AddressingMode::ZeroPage),
-        0x76 => ("ror", 2, AddressingMode::ZeroPageX),
-        0x6e => ("ror", 3, AddressingMode::Absolute),
-        0x7e => ("ror", 3, AddressingMode::AbsoluteX),
-
-        0x40 => ("rti", 1, AddressingMode::Implied),
-
-        0x60 => ("rts", 1, AddressingMode::Implied),
-
-        0xe9 => ("sbc", 2, AddressingMode::Immediate),
-        0xe5 => ("sbc", 2, AddressingMode::ZeroPage),
-        0xf5 => ("sbc", 2, AddressingMode::ZeroPageX),
-        0xed => ("sbc", 3, AddressingMode::Absolute),
-        0xfd => ("sbc", 3, AddressingMode::AbsoluteX),
-        0xf9 => ("sbc", 3, AddressingMode::AbsoluteY),
-        0xe1 => ("sbc", 2, AddressingMode::IndexedIndirect),
-        0xf1 => ("sbc", 2, AddressingMode::IndirectIndexed),
-
-        0x38 => ("sec", 1, AddressingMode::Implied),
-
-        0xf8 => ("sed", 1, AddressingMode::Implied),
-
-        0x78 => ("sei", 1, AddressingMode::Implied),
-
-        0x85 => ("sta", 2, AddressingMode::ZeroPage),
-        0x95 => ("sta", 2, AddressingMode::ZeroPageX),
-        0x8d => ("sta", 3, AddressingMode::Absolute),
-        0x9d => ("sta", 3, AddressingMode::AbsoluteX),
-        0x99 => ("sta", 3, AddressingMode::AbsoluteY),
-        0x81 => ("sta", 2, AddressingMode::IndexedIndirect),
-        0x91 => ("sta", 2, AddressingMode::IndirectIndexed),
-
-        0x86 => ("stx", 2, AddressingMode::ZeroPage),
-        0x96 => ("stx", 2, AddressingMode::ZeroPageY),
-        0x8e => ("stx", 3, AddressingMode::Absolute),
-
-        0x84 => ("sty", 2, AddressingMode::ZeroPage),
-        0x94 => ("sty", 2, AddressingMode::ZeroPageX),
-        0x8c => ("sty", 3, AddressingMode::Absolute),
-
-        0xaa => ("tax", 1, AddressingMode::Implied),
-
-        0xa8 => ("tay", 1, AddressingMode::Implied),
-
-        0xba => ("tsx", 1, AddressingMode::Implied),
-
-        0x8a => ("txa", 1, AddressingMode::Implied),
-
-        0x9a => ("txs", 1, AddressingMode::Implied),
-
-        0x98 => ("tya", 1, AddressingMode::Implied),
-
-
-        // Unofficials
-
-
-        0x93 => ("ahx", 2, AddressingMode::IndirectIndexed),
-        0x9f => ("ahx", 3, AddressingMode::AbsoluteY),
-
-        0x4b => ("alr", 2, AddressingMode::Immediate),
-
-        0x0b => ("anc", 2, AddressingMode::Immediate),
-        0x2b => ("anc", 2, AddressingMode::Immediate),
-
-        0x6b => ("arr", 2, AddressingMode::Immediate),
-
-        0xcb => ("axs", 2, AddressingMode::Immediate),
-
-        0xc7 => ("dcp", 2, AddressingMode::ZeroPage),
-        0xd7 => ("dcp", 2, AddressingMode::ZeroPageX),
-        0xcf => ("dcp", 3, AddressingMode::Absolute),
-        0xdf => ("dcp", 3, AddressingMode::AbsoluteX),
-        0xdb => ("dcp", 3, AddressingMode::AbsoluteY),
-        0xd3 => ("dcp", 2, AddressingMode::IndirectIndexed),
-        0xc3 => ("dcp", 2, AddressingMode::IndexedIndirect),
-
-        0xe7 => ("isb", 2, AddressingMode::ZeroPage),
-        0xf7 => ("isb", 2, AddressingMode::ZeroPageX),
-        0xef => ("isb", 3, AddressingMode::Absolute),
-        0xff => ("isb", 3, AddressingMode::AbsoluteX),
-        0xfb => ("isb", 3, AddressingMode::AbsoluteY),
-        0xe3 => ("isb", 2, AddressingMode::IndexedIndirect),
-        0xf3 => ("isb", 2, AddressingMode::IndirectIndexed),
-
-        0xbb => ("las", 3, AddressingMode::AbsoluteY),
-
-        0xa7 => ("lax", 2, AddressingMode::ZeroPage),
-        0xb7 => ("lax", 2, AddressingMode::ZeroPageY),
-        0xaf => ("lax", 3, AddressingMode::Absolute),
-        0xbf => ("lax", 3, AddressingMode::AbsoluteY),
-        0xa3 => ("lax", 2, AddressingMode::IndexedIndirect),
-        0xb3 => ("lax", 2, AddressingMode::IndirectIndexed),
-        0xab => ("lxa", 2, AddressingMode::Immediate),
-
-        0x80 => ("hex", 2, AddressingMode::Immediate),
-        0x82 => ("hex", 2, AddressingMode::Immediate),
-        0x89 => ("hex", 2, AddressingMode::Immediate),
-        0xc2 => ("hex", 2, AddressingMode::Immediate),
-        0xe2 => ("hex", 2, AddressingMode::Immediate),
-        0x04 => ("hex", 2, AddressingMode::ZeroPage),
-        0x44 => ("hex", 2, AddressingMode::ZeroPage),
-        0x64 => ("hex", 2, AddressingMode::ZeroPage),
-        0x14 => ("hex", 2, AddressingMode::ZeroPageX),
-        0x34 => ("hex", 2, AddressingMode::ZeroPageX),
-        0x54 => ("hex", 2, AddressingMode::ZeroPageX),
-        0x74 => ("hex", 2, AddressingMode::ZeroPageX),
-        0xd4 => ("hex", 2, AddressingMode::ZeroPageX),
-        0xf4 => ("hex", 2, AddressingMode::ZeroPageX),
-        0x0c => ("hex", 3, AddressingMode::Absolute),
-        0x1c => ("hex", 3, AddressingMode::AbsoluteX),
-        0x3c => ("hex", 3, AddressingMode::AbsoluteX),
-        0x5c => ("hex", 3, AddressingMode::AbsoluteX),
-        0x7c => ("hex", 3, AddressingMode::AbsoluteX),
-        0xdc => ("hex", 3, AddressingMode::AbsoluteX),
-        0xfc => ("hex", 3, AddressingMode::AbsoluteX),
-        0x02 => ("nop", 1, AddressingMode::Implied),
-        0x12 => ("nop", 1, AddressingMode::Implied),
-        0x22 => ("nop", 1, AddressingMode::Implied),
-        0x32 => ("nop", 1, AddressingMode::Implied),
-        0x42 => ("nop", 1, AddressingMode::Implied),
-        0x52 => ("nop", 1, AddressingMode::Implied),
-        0x62 => ("nop", 1, AddressingMode::Implied),
-        0x72 => ("nop", 1, AddressingMode::Implied),
-        0x92 => ("nop", 1, AddressingMode::Implied),
-        0xb2 => ("nop", 1, AddressingMode::Implied),
-        0xd2 => ("nop", 1, AddressingMode::Implied),
-        0xf2 => ("nop", 1, AddressingMode::Implied),
-        0x1a => ("nop", 1, AddressingMode::Implied),
-        0x3a => ("nop", 1, AddressingMode::Implied),
-        0x5a => ("nop", 1, AddressingMode::Implied),
-        0x7a => ("nop", 1, AddressingMode::Implied),
-        0xda => ("nop", 1, AddressingMode::Implied),
-        0xfa => ("nop", 1, AddressingMode::Implied),
-
-        0x27 => ("rla", 2, AddressingMode::ZeroPage),
-        0x37 => ("rla", 2, AddressingMode::ZeroPageX),
-        0x2f => ("rla", 3, AddressingMode::Absolute),
-        0x3f => ("rla", 3, AddressingMode::AbsoluteX),
-        0x3b => ("rla", 3, AddressingMode::AbsoluteY),
-        0x33 => ("rla", 2, AddressingMode::IndirectIndexed),
-        0x23 => ("rla", 2, AddressingMode::IndexedIndirect),
-
-        0x67 => ("rra", 2, AddressingMode::ZeroPage),
-        0x77 => ("rra", 2, AddressingMode::ZeroPageX),
-        0x6f => ("rra", 3, AddressingMode::Absolute),
-        0x7f => ("rra", 3, AddressingMode::AbsoluteX),
-        0x7b => ("rra", 3, AddressingMode::AbsoluteY),
-        0x63 => ("rra", 2, AddressingMode::IndexedIndirect),
-        0x73 => ("rra", 2, AddressingMode::IndirectIndexed),
-
-        0x87 => ("sax", 2, AddressingMode::ZeroPage),
-        0x97 => ("sax", 2, AddressingMode::ZeroPageY),
-        0x8f => ("sax", 3, AddressingMode::Absolute),
-        0x83 => ("sax", 2, AddressingMode::IndexedIndirect),
-
-        0xeb => ("sbc", 2, AddressingMode::Immediate),
-
-        0x9e => ("shx", 3, AddressingMode::AbsoluteY),
-
-        0x9c => ("shy", 3, AddressingMode::AbsoluteX),
-
-        0x07 => ("slo", 2, AddressingMode::ZeroPage),
-        0x17 => ("slo", 2, AddressingMode::ZeroPageX),
-        0x0f => ("slo", 3, AddressingMode::Absolute),
-        0x1f => ("slo", 3, AddressingMode::AbsoluteX),
-        0x1b => ("slo", 3, AddressingMode::AbsoluteY),
-        0x03 => ("slo", 2, AddressingMode::IndexedIndirect),
-        0x13 => ("slo", 2, AddressingMode::IndirectIndexed),
-
-        0x47 => ("sre", 2, AddressingMode::ZeroPage),
-        0x57 => ("sre", 2, AddressingMode::ZeroPageX),
-        0x4f => ("sre", 3, AddressingMode::Absolute),
-        0x5f => ("sre", 3, AddressingMode::AbsoluteX),
-        0x5b => ("sre", 3, AddressingMode::AbsoluteY),
-        0x43 => ("sre", 2, AddressingMode::IndexedIndirect),
-        0x53 => ("sre", 2, AddressingMode::IndirectIndexed),
-
-        0x9b => ("tas", 3, AddressingMode::AbsoluteY),
-
-        0x8b => ("xaa", 2, AddressingMode::Immediate)
+        0x9a => ("txs", 1, 2, AddressingMode::Implied),
+
+        0x98 => ("tya", 1, 2, AddressingMode::Implied),
+
+        _ => return None
+    };
+
+    let (mnemonic, len, cycles, mode) = ret;
+
+    Some(OpCode::new(*code, mnemonic, len, cycles, mode))
+}
+
+/// Unofficial (illegal) 6502 opcode, with the ca65 mnemonics
+pub fn get_unofficial_opcode(code: &u8) -> Option<OpCode> {
+    let ret = match code {
+        0x03 => ("slo", 2, 8, AddressingMode::IndexedIndirect),
+        0x07 => ("slo", 2, 5, AddressingMode::ZeroPage),
+        0x0f => ("slo", 3, 6, AddressingMode::Absolute),
+        0x13 => ("slo", 2, 8, AddressingMode::IndirectIndexed),
+        0x17 => ("slo", 2, 6, AddressingMode::ZeroPageX),
+        0x1b => ("slo", 3, 7, AddressingMode::AbsoluteY),
+        0x1f => ("slo", 3, 7, AddressingMode::AbsoluteX),
+
+        0x23 => ("rla", 2, 8, AddressingMode::IndexedIndirect),
+        0x27 => ("rla", 2, 5, AddressingMode::ZeroPage),
+        0x2f => ("rla", 3, 6, AddressingMode::Absolute),
+        0x33 => ("rla", 2, 8, AddressingMode::IndirectIndexed),
+        0x37 => ("rla", 2, 6, AddressingMode::ZeroPageX),
+        0x3b => ("rla", 3, 7, AddressingMode::AbsoluteY),
+        0x3f => ("rla", 3, 7, AddressingMode::AbsoluteX),
+
+        0x43 => ("sre", 2, 8, AddressingMode::IndexedIndirect),
+        0x47 => ("sre", 2, 5, AddressingMode::ZeroPage),
+        0x4f => ("sre", 3, 6, AddressingMode::Absolute),
+        0x53 => ("sre", 2, 8, AddressingMode::IndirectIndexed),
+        0x57 => ("sre", 2, 6, AddressingMode::ZeroPageX),
+        0x5b => ("sre", 3, 7, AddressingMode::AbsoluteY),
+        0x5f => ("sre", 3, 7, AddressingMode::AbsoluteX),
+
+        0x63 => ("rra", 2, 8, AddressingMode::IndexedIndirect),
+        0x67 => ("rra", 2, 5, AddressingMode::ZeroPage),
+        0x6f => ("rra", 3, 6, AddressingMode::Absolute),
+        0x73 => ("rra", 2, 8, AddressingMode::IndirectIndexed),
+        0x77 => ("rra", 2, 6, AddressingMode::ZeroPageX),
+        0x7b => ("rra", 3, 7, AddressingMode::AbsoluteY),
+        0x7f => ("rra", 3, 7, AddressingMode::AbsoluteX),
+
+        0xc3 => ("dcp", 2, 8, AddressingMode::IndexedIndirect),
+        0xc7 => ("dcp", 2, 5, AddressingMode::ZeroPage),
+        0xcf => ("dcp", 3, 6, AddressingMode::Absolute),
+        0xd3 => ("dcp", 2, 8, AddressingMode::IndirectIndexed),
+        0xd7 => ("dcp", 2, 6, AddressingMode::ZeroPageX),
+        0xdb => ("dcp", 3, 7, AddressingMode::AbsoluteY),
+        0xdf => ("dcp", 3, 7, AddressingMode::AbsoluteX),
+
+        0xe3 => ("isc", 2, 8, AddressingMode::IndexedIndirect),
+        0xe7 => ("isc", 2, 5, AddressingMode::ZeroPage),
+        0xef => ("isc", 3, 6, AddressingMode::Absolute),
+        0xf3 => ("isc", 2, 8, AddressingMode::IndirectIndexed),
+        0xf7 => ("isc", 2, 6, AddressingMode::ZeroPageX),
+        0xfb => ("isc", 3, 7, AddressingMode::AbsoluteY),
+        0xff => ("isc", 3, 7, AddressingMode::AbsoluteX),
+
+        0x83 => ("sax", 2, 6, AddressingMode::IndexedIndirect),
+        0x87 => ("sax", 2, 3, AddressingMode::ZeroPage),
+        0x8f => ("sax", 3, 4, AddressingMode::Absolute),
+        0x97 => ("sax", 2, 4, AddressingMode::ZeroPageY),
+
+        0xa3 => ("lax", 2, 6, AddressingMode::IndexedIndirect),
+        0xa7 => ("lax", 2, 3, AddressingMode::ZeroPage),
+        0xaf => ("lax", 3, 4, AddressingMode::Absolute),
+        0xb3 => ("lax", 2, 5, AddressingMode::IndirectIndexed),
+        0xb7 => ("lax", 2, 4, AddressingMode::ZeroPageY),
+        0xbf => ("lax", 3, 4, AddressingMode::AbsoluteY),
+
+        0xab => ("lxa", 2, 2, AddressingMode::Immediate),
+
+        0x0b => ("anc", 2, 2, AddressingMode::Immediate),
+        0x2b => ("anc", 2, 2, AddressingMode::Immediate),
+
+        0x4b => ("alr", 2, 2, AddressingMode::Immediate),
+
+        0x6b => ("arr", 2, 2, AddressingMode::Immediate),
+
+        0xcb => ("axs", 2, 2, AddressingMode::Immediate),
+
+        0x8b => ("ane", 2, 2, AddressingMode::Immediate),
+
+        0xeb => ("sbc", 2, 2, AddressingMode::Immediate),
+
+        0x93 => ("sha", 2, 6, AddressingMode::IndirectIndexed),
+        0x9f => ("sha", 3, 5, AddressingMode::AbsoluteY),
+
+        0x9e => ("shx", 3, 5, AddressingMode::AbsoluteY),
+
+        0x9c => ("shy", 3, 5, AddressingMode::AbsoluteX),
+
+        0x9b => ("tas", 3, 5, AddressingMode::AbsoluteY),
+
+        0xbb => ("las", 3, 4, AddressingMode::AbsoluteY),
+
+        0x1a => ("nop", 1, 2, AddressingMode::Implied),
+        0x3a => ("nop", 1, 2, AddressingMode::Implied),
+        0x5a => ("nop", 1, 2, AddressingMode::Implied),
+        0x7a => ("nop", 1, 2, AddressingMode::Implied),
+        0xda => ("nop", 1, 2, AddressingMode::Implied),
+        0xfa => ("nop", 1, 2, AddressingMode::Implied),
+        0x80 => ("nop", 2, 2, AddressingMode::Immediate),
+        0x82 => ("nop", 2, 2, AddressingMode::Immediate),
+        0x89 => ("nop", 2, 2, AddressingMode::Immediate),
+        0xc2 => ("nop", 2, 2, AddressingMode::Immediate),
+        0xe2 => ("nop", 2, 2, AddressingMode::Immediate),
+        0x04 => ("nop", 2, 3, AddressingMode::ZeroPage),
+        0x44 => ("nop", 2, 3, AddressingMode::ZeroPage),
+        0x64 => ("nop", 2, 3, AddressingMode::ZeroPage),
+        0x14 => ("nop", 2, 4, AddressingMode::ZeroPageX),
+        0x34 => ("nop", 2, 4, AddressingMode::ZeroPageX),
+        0x54 => ("nop", 2, 4, AddressingMode::ZeroPageX),
+        0x74 => ("nop", 2, 4, AddressingMode::ZeroPageX),
+        0xd4 => ("nop", 2, 4, AddressingMode::ZeroPageX),
+        0xf4 => ("nop", 2, 4, AddressingMode::ZeroPageX),
+        0x0c => ("nop", 3, 4, AddressingMode::Absolute),
+        0x1c => ("nop", 3, 4, AddressingMode::AbsoluteX),
+        0x3c => ("nop", 3, 4, AddressingMode::AbsoluteX),
+        0x5c => ("nop", 3, 4, AddressingMode::AbsoluteX),
+        0x7c => ("nop", 3, 4, AddressingMode::AbsoluteX),
+        0xdc => ("nop", 3, 4, AddressingMode::AbsoluteX),
+        0xfc => ("nop", 3, 4, AddressingMode::AbsoluteX),
+
+        // Halt the CPU
+        0x02 => ("jam", 1, 0, AddressingMode::Implied),
+        0x12 => ("jam", 1, 0, AddressingMode::Implied),
+        0x22 => ("jam", 1, 0, AddressingMode::Implied),
+        0x32 => ("jam", 1, 0, AddressingMode::Implied),
+        0x42 => ("jam", 1, 0, AddressingMode::Implied),
+        0x52 => ("jam", 1, 0, AddressingMode::Implied),
+        0x62 => ("jam", 1, 0, AddressingMode::Implied),
+        0x72 => ("jam", 1, 0, AddressingMode::Implied),
+        0x92 => ("jam", 1, 0, AddressingMode::Implied),
+        0xb2 => ("jam", 1, 0, AddressingMode::Implied),
+        0xd2 => ("jam", 1, 0, AddressingMode::Implied),
+        0xf2 => ("jam", 1, 0, AddressingMode::Implied),
+
+        _ => return None
     };
 
-    let (mnemonic, len, mode) = ret;
-    let opcode = OpCode::new(*code, mnemonic, len, mode);
+    let (mnemonic, len, cycles, mode) = ret;
+
+    Some(OpCode::new(*code, mnemonic, len, cycles, mode))
+}
+
+/// Official or unofficial 6502 opcode
+pub fn get_nes_opcode(code: &u8) -> Option<OpCode> {
+    get_official_opcode(code).or_else(|| get_unofficial_opcode(code))
+}
+
+/// Opcode of a mnemonic and an addressing mode, official opcodes first
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::utils::addressing::AddressingMode;
+/// use nes_utils::utils::opcode::find_opcode;
+///
+/// assert_eq!(find_opcode("lda", AddressingMode::Immediate).unwrap().code, 0xa9);
+/// assert_eq!(find_opcode("nop", AddressingMode::Implied).unwrap().code, 0xea);
+/// assert!(find_opcode("lda", AddressingMode::Relative).is_none());
+/// ```
+pub fn find_opcode(mnemonic: &str, mode: AddressingMode) -> Option<OpCode> {
+    let matches = |opcode: &OpCode| opcode.mnemonic == mnemonic && opcode.mode == mode;
+
+    (0..=255u8)
+        .filter_map(|code| get_official_opcode(&code))
+        .find(matches)
+        .or_else(|| {
+            (0..=255u8)
+                .filter_map(|code| get_unofficial_opcode(&code))
+                .find(matches)
+        })
+}
+
+/// Opcodes decoded as instructions, the other bytes are data
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::utils::opcode::OpCodeSet;
+///
+/// assert!(OpCodeSet::Official.decode(&0xa7).is_none());
+/// assert_eq!(OpCodeSet::Unofficial.decode(&0xa7).unwrap().mnemonic, "lax");
+/// assert!((0..=255).all(|code| OpCodeSet::Unofficial.decode(&code).is_some()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OpCodeSet {
+    /// The 151 documented opcodes
+    #[default]
+    Official,
+    /// The documented and the undocumented opcodes
+    Unofficial
+}
+
+impl OpCodeSet {
+    pub fn decode(&self, code: &u8) -> Option<OpCode> {
+        match self {
+            Self::Official => get_official_opcode(code),
+            Self::Unofficial => get_nes_opcode(code)
+        }
+    }
+}
 
-    Some(opcode)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Official opcodes: byte, mnemonic, mode, length and base cycles
+    const REFERENCE: &str = "
+        00 brk imp  1 7
+        01 ora indx 2 6
+        05 ora zp   2 3
+        06 asl zp   2 5
+        08 php imp  1 3
+        09 ora imm  2 2
+        0a asl acc  1 2
+        0d ora abs  3 4
+        0e asl abs  3 6
+        10 bpl rel  2 2
+        11 ora indy 2 5
+        15 ora zpx  2 4
+        16 asl zpx  2 6
+        18 clc imp  1 2
+        19 ora absy 3 4
+        1d ora absx 3 4
+        1e asl absx 3 7
+        20 jsr abs  3 6
+        21 and indx 2 6
+        24 bit zp   2 3
+        25 and zp   2 3
+        26 rol zp   2 5
+        28 plp imp  1 4
+        29 and imm  2 2
+        2a rol acc  1 2
+        2c bit abs  3 4
+        2d and abs  3 4
+        2e rol abs  3 6
+        30 bmi rel  2 2
+        31 and indy 2 5
+        35 and zpx  2 4
+        36 rol zpx  2 6
+        38 sec imp  1 2
+        39 and absy 3 4
+        3d and absx 3 4
+        3e rol absx 3 7
+        40 rti imp  1 6
+        41 eor indx 2 6
+        45 eor zp   2 3
+        46 lsr zp   2 5
+        48 pha imp  1 3
+        49 eor imm  2 2
+        4a lsr acc  1 2
+        4c jmp abs  3 3
+        4d eor abs  3 4
+        4e lsr abs  3 6
+        50 bvc rel  2 2
+        51 eor indy 2 5
+        55 eor zpx  2 4
+        56 lsr zpx  2 6
+        58 cli imp  1 2
+        59 eor absy 3 4
+        5d eor absx 3 4
+        5e lsr absx 3 7
+        60 rts imp  1 6
+        61 adc indx 2 6
+        65 adc zp   2 3
+        66 ror zp   2 5
+        68 pla imp  1 4
+        69 adc imm  2 2
+        6a ror acc  1 2
+        6c jmp ind  3 5
+        6d adc abs  3 4
+        6e ror abs  3 6
+        70 bvs rel  2 2
+        71 adc indy 2 5
+        75 adc zpx  2 4
+        76 ror zpx  2 6
+        78 sei imp  1 2
+        79 adc absy 3 4
+        7d adc absx 3 4
+        7e ror absx 3 7
+        81 sta indx 2 6
+        84 sty zp   2 3
+        85 sta zp   2 3
+        86 stx zp   2 3
+        88 dey imp  1 2
+        8a txa imp  1 2
+        8c sty abs  3 4
+        8d sta abs  3 4
+        8e stx abs  3 4
+        90 bcc rel  2 2
+        91 sta indy 2 6
+        94 sty zpx  2 4
+        95 sta zpx  2 4
+        96 stx zpy  2 4
+        98 tya imp  1 2
+        99 sta absy 3 5
+        9a txs imp  1 2
+        9d sta absx 3 5
+        a0 ldy imm  2 2
+        a1 lda indx 2 6
+        a2 ldx imm  2 2
+        a4 ldy zp   2 3
+        a5 lda zp   2 3
+        a6 ldx zp   2 3
+        a8 tay imp  1 2
+        a9 lda imm  2 2
+        aa tax imp  1 2
+        ac ldy abs  3 4
+        ad lda abs  3 4
+        ae ldx abs  3 4
+        b0 bcs rel  2 2
+        b1 lda indy 2 5
+        b4 ldy zpx  2 4
+        b5 lda zpx  2 4
+        b6 ldx zpy  2 4
+        b8 clv imp  1 2
+        b9 lda absy 3 4
+        ba tsx imp  1 2
+        bc ldy absx 3 4
+        bd lda absx 3 4
+        be ldx absy 3 4
+        c0 cpy imm  2 2
+        c1 cmp indx 2 6
+        c4 cpy zp   2 3
+        c5 cmp zp   2 3
+        c6 dec zp   2 5
+        c8 iny imp  1 2
+        c9 cmp imm  2 2
+        ca dex imp  1 2
+        cc cpy abs  3 4
+        cd cmp abs  3 4
+        ce dec abs  3 6
+        d0 bne rel  2 2
+        d1 cmp indy 2 5
+        d5 cmp zpx  2 4
+        d6 dec zpx  2 6
+        d8 cld imp  1 2
+        d9 cmp absy 3 4
+        dd cmp absx 3 4
+        de dec absx 3 7
+        e0 cpx imm  2 2
+        e1 sbc indx 2 6
+        e4 cpx zp   2 3
+        e5 sbc zp   2 3
+        e6 inc zp   2 5
+        e8 inx imp  1 2
+        e9 sbc imm  2 2
+        ea nop imp  1 2
+        ec cpx abs  3 4
+        ed sbc abs  3 4
+        ee inc abs  3 6
+        f0 beq rel  2 2
+        f1 sbc indy 2 5
+        f5 sbc zpx  2 4
+        f6 inc zpx  2 6
+        f8 sed imp  1 2
+        f9 sbc absy 3 4
+        fd sbc absx 3 4
+        fe inc absx 3 7
+    ";
+
+    fn mode(name: &str) -> AddressingMode {
+        match name {
+            "imp" => AddressingMode::Implied,
+            "acc" => AddressingMode::Accumulator,
+            "imm" => AddressingMode::Immediate,
+            "zp" => AddressingMode::ZeroPage,
+            "zpx" => AddressingMode::ZeroPageX,
+            "zpy" => AddressingMode::ZeroPageY,
+            "abs" => AddressingMode::Absolute,
+            "absx" => AddressingMode::AbsoluteX,
+            "absy" => AddressingMode::AbsoluteY,
+            "ind" => AddressingMode::Indirect,
+            "indx" => AddressingMode::IndexedIndirect,
+            "indy" => AddressingMode::IndirectIndexed,
+            "rel" => AddressingMode::Relative,
+            _ => panic!("unknown addressing mode {}", name)
+        }
+    }
+
+    #[test]
+    fn official_opcodes_match_reference() {
+        let mut expected = [None; 256];
+
+        for line in REFERENCE.lines().filter(|line| !line.trim().is_empty()) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let code = u8::from_str_radix(fields[0], 16).unwrap();
+
+            assert!(expected[code as usize].is_none(), "duplicate {:02x}", code);
+            expected[code as usize] = Some((
+                fields[1],
+                mode(fields[2]),
+                fields[3].parse::<u8>().unwrap(),
+                fields[4].parse::<u8>().unwrap()
+            ));
+        }
+
+        assert_eq!(expected.iter().flatten().count(), 151);
+
+        for code in 0..=255u8 {
+            let actual = get_official_opcode(&code)
+                .map(|opcode| (opcode.mnemonic, opcode.mode, opcode.len, opcode.cycles));
+
+            assert_eq!(actual, expected[code as usize], "opcode {:02x}", code);
+        }
+    }
 }
@@ -40,3 +40,164 @@ pub fn get_mapped_register(address: u16) -> Option<String> {
 
     Some(ret)
 }
+
+/// Length counter values of the APU, indexed by the 5 written bits
+const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14,
+    12, 16, 24, 18, 48, 20, 96, 22, 192, 24, 72, 26, 16, 28, 32, 30
+];
+
+/// Duty cycles of the pulse channels
+const DUTY_CYCLES: [&str; 4] = ["12.5%", "25%", "50%", "75%"];
+
+/// Description of the bits written to a PPU, APU or joypad register,
+/// `None` for the registers without bit fields (`PPUDATA`, `PPUADDR`, ...)
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use nes_utils::utils::registers::register_bits;
+///
+/// assert_eq!(
+///     register_bits(0x2000, 0x90).unwrap(),
+///     "NMI on, 8x8 sprites, BG $1000, sprites $0000, VRAM +1, NT $2000"
+/// );
+/// assert_eq!(register_bits(0x4015, 0x0f).unwrap(), "pulse 1, pulse 2, triangle, noise on");
+/// assert_eq!(register_bits(0x2007, 0x00), None);
+/// ```
+pub fn register_bits(address: u16, value: u8) -> Option<String> {
+    let bit = |n: u8| value & (1 << n) != 0;
+    let flag = |n: u8, on: &str, off: &str| String::from(if bit(n) { on } else { off });
+
+    let fields: Vec<String> = match address {
+        0x2000 => {
+            let mut ret = vec![flag(7, "NMI on", "NMI off")];
+
+            if bit(6) {
+                ret.push(String::from("EXT out"));
+            }
+            ret.push(flag(5, "8x16 sprites", "8x8 sprites"));
+            ret.push(flag(4, "BG $1000", "BG $0000"));
+            // The 8x16 sprites select their pattern table
+            if !bit(5) {
+                ret.push(flag(3, "sprites $1000", "sprites $0000"));
+            }
+            ret.push(flag(2, "VRAM +32", "VRAM +1"));
+            ret.push(format!("NT ${:04x}", 0x2000 + 0x400 * (value as u16 & 0x03)));
+            ret
+        },
+        0x2001 => {
+            let mut ret = vec![
+                flag(3, "BG on", "BG off"),
+                flag(4, "sprites on", "sprites off")
+            ];
+
+            if bit(1) {
+                ret.push(String::from("BG left"));
+            }
+            if bit(2) {
+                ret.push(String::from("sprites left"));
+            }
+            if bit(0) {
+                ret.push(String::from("greyscale"));
+            }
+
+            let colors: Vec<&str> = [(5, "red"), (6, "green"), (7, "blue")]
+                .iter()
+                .filter(|(n, _)| bit(*n))
+                .map(|(_, color)| *color)
+                .collect();
+
+            if !colors.is_empty() {
+                ret.push(format!("emphasis {}", colors.join(" ")));
+            }
+            ret
+        },
+        0x2003 => vec![format!("OAM ${:02x}", value)],
+        0x4014 => vec![format!("OAM DMA from ${:02x}00", value)],
+
+        // Pulse and noise envelopes
+        0x4000 | 0x4004 | 0x400c => {
+            let mut ret = Vec::new();
+
+            if address != 0x400c {
+                ret.push(format!("duty {}", DUTY_CYCLES[value as usize >> 6]));
+            }
+            if bit(5) {
+                ret.push(String::from("halt"));
+            }
+            ret.push(format!("{} {}", flag(4, "volume", "envelope"), value & 0x0f));
+            ret
+        },
+        0x4001 | 0x4005 => match bit(7) {
+            true => {
+                let mut ret = vec![
+                    String::from("sweep on"),
+                    format!("period {}", (value >> 4) & 0x07)
+                ];
+
+                if bit(3) {
+                    ret.push(String::from("negate"));
+                }
+                ret.push(format!("shift {}", value & 0x07));
+                ret
+            },
+            false => vec![String::from("sweep off")]
+        },
+        0x4002 | 0x4006 | 0x400a => vec![format!("timer low ${:02x}", value)],
+        0x4003 | 0x4007 | 0x400b => vec![
+            format!("length {}", LENGTH_TABLE[value as usize >> 3]),
+            format!("timer high {}", value & 0x07)
+        ],
+        0x4008 => {
+            let mut ret = Vec::new();
+
+            if bit(7) {
+                ret.push(String::from("halt"));
+            }
+            ret.push(format!("linear {}", value & 0x7f));
+            ret
+        },
+        0x400e => vec![
+            flag(7, "short mode", "long mode"),
+            format!("period {}", value & 0x0f)
+        ],
+        0x400f => vec![format!("length {}", LENGTH_TABLE[value as usize >> 3])],
+        0x4010 => {
+            let mut ret = vec![flag(7, "IRQ on", "IRQ off")];
+
+            if bit(6) {
+                ret.push(String::from("loop"));
+            }
+            ret.push(format!("rate {}", value & 0x0f));
+            ret
+        },
+        0x4011 => vec![format!("level {}", value & 0x7f)],
+        0x4012 => vec![format!("sample ${:04x}", 0xc000 + 64 * value as u16)],
+        0x4013 => vec![format!("{} bytes", 16 * value as u16 + 1)],
+        0x4015 => {
+            let channels: Vec<&str> = ["pulse 1", "pulse 2", "triangle", "noise", "DMC"]
+                .iter()
+                .enumerate()
+                .filter(|(n, _)| bit(*n as u8))
+                .map(|(_, channel)| *channel)
+                .collect();
+
+            match channels.is_empty() {
+                true => vec![String::from("all channels off")],
+                false => vec![format!("{} on", channels.join(", "))]
+            }
+        },
+        0x4016 => vec![flag(0, "strobe on", "strobe off")],
+        // Written, $4017 is the APU frame counter
+        0x4017 => vec![
+            flag(7, "5-step", "4-step"),
+            flag(6, "IRQ off", "IRQ on")
+        ],
+        _ => return None
+    };
+
+    Some(fields.join(", "))
+}
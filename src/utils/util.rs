@@ -11,12 +11,13 @@ pub fn path_to_name(path: &str) -> &str {
     name_vec[0]
 }
 
-pub fn create_and_write_file(path: &str, data: &[u8]) {
-    match File::create(path) {
-        Ok(mut file) => file.write_all(data)
-            .expect("Unable to write"),
-        Err(_) => panic!("{}", NesError::FileInvalid)
-    };
+pub fn create_and_write_file(path: &str, data: &[u8]) -> Result<(), NesError> {
+    File::create(path)
+        .and_then(|mut file| file.write_all(data))
+        .map_err(|source| NesError::FileInvalid {
+            path: String::from(path),
+            source
+        })
 }
 
 pub fn join_bytes(vec: &[u8], sep: &str) -> String {
@@ -58,3 +59,23 @@ pub fn vec_bytes_to_string(vec: &Vec<u8>) -> String {
 
     ret.join(" ")
 }
+
+/// Quoted and escaped JSON string
+pub fn json_string(value: &str) -> String {
+    let mut ret = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            '\n' => ret.push_str("\\n"),
+            '\r' => ret.push_str("\\r"),
+            '\t' => ret.push_str("\\t"),
+            c if (c as u32) < 0x20 => ret.push_str(&format!("\\u{:04x}", c as u32)),
+            c => ret.push(c)
+        }
+    }
+
+    ret.push('"');
+    ret
+}